@@ -1,3 +1,6 @@
+pub mod grid_search;
+pub mod render;
+
 use std::fmt::{Debug, Formatter};
 use std::mem::{ManuallyDrop, MaybeUninit};
 use std::ops::{Add, AddAssign, Index, IndexMut, Sub, SubAssign};
@@ -284,6 +287,74 @@ impl CompassDirection {
         }
     }
 }
+
+/** All eight directions, compass and diagonal, in clockwise order */
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum OrdinalDirection {
+    NORTH,
+    NORTHEAST,
+    EAST,
+    SOUTHEAST,
+    SOUTH,
+    SOUTHWEST,
+    WEST,
+    NORTHWEST,
+}
+
+impl OrdinalDirection {
+    pub const ALL: [OrdinalDirection; 8] = [
+        OrdinalDirection::NORTH,
+        OrdinalDirection::NORTHEAST,
+        OrdinalDirection::EAST,
+        OrdinalDirection::SOUTHEAST,
+        OrdinalDirection::SOUTH,
+        OrdinalDirection::SOUTHWEST,
+        OrdinalDirection::WEST,
+        OrdinalDirection::NORTHWEST,
+    ];
+
+    fn rotate(self, eighths: usize) -> Self {
+        Self::ALL[(self as usize + eighths) % 8]
+    }
+
+    pub fn turn_right_45(self) -> Self {
+        self.rotate(1)
+    }
+
+    pub fn turn_left_45(self) -> Self {
+        self.rotate(7)
+    }
+
+    pub fn opposite(self) -> Self {
+        self.rotate(4)
+    }
+
+    /** Row and column offset of a single step */
+    pub fn offset(self) -> (isize, isize) {
+        match self {
+            OrdinalDirection::NORTH => (-1, 0),
+            OrdinalDirection::NORTHEAST => (-1, 1),
+            OrdinalDirection::EAST => (0, 1),
+            OrdinalDirection::SOUTHEAST => (1, 1),
+            OrdinalDirection::SOUTH => (1, 0),
+            OrdinalDirection::SOUTHWEST => (1, -1),
+            OrdinalDirection::WEST => (0, -1),
+            OrdinalDirection::NORTHWEST => (-1, -1),
+        }
+    }
+}
+
+impl From<CompassDirection> for OrdinalDirection {
+    fn from(direction: CompassDirection) -> Self {
+        match direction {
+            CompassDirection::NORTH => OrdinalDirection::NORTH,
+            CompassDirection::EAST => OrdinalDirection::EAST,
+            CompassDirection::SOUTH => OrdinalDirection::SOUTH,
+            CompassDirection::WEST => OrdinalDirection::WEST,
+        }
+    }
+}
+
 /**
 2D Indices, unconstrained for arbitrary movement - need to be converted to Index2D for usage in
 Vec2D access
@@ -398,6 +469,18 @@ impl SubAssign<CompassDirection> for Index2D {
     }
 }
 
+impl Add<OrdinalDirection> for Index2D {
+    type Output = Index2D;
+
+    fn add(self, rhs: OrdinalDirection) -> Self::Output {
+        let (row_offset, column_offset) = rhs.offset();
+        match (self.row.checked_add_signed(row_offset), self.column.checked_add_signed(column_offset)) {
+            (Some(row), Some(column)) if self.plausible() => Index2D { row, column },
+            _ => Index2D::IMPLAUSIBLE,
+        }
+    }
+}
+
 impl Add for Location2D {
     type Output = Location2D;
 
@@ -443,6 +526,15 @@ impl SubAssign<CompassDirection> for Location2D {
     }
 }
 
+impl Add<OrdinalDirection> for Location2D {
+    type Output = Location2D;
+
+    fn add(self, rhs: OrdinalDirection) -> Self::Output {
+        let (row_offset, column_offset) = rhs.offset();
+        Location2D { row: self.row + row_offset as i64, column: self.column + column_offset as i64 }
+    }
+}
+
 impl Sub for Location2D {
     type Output = Location2D;
 
@@ -539,7 +631,7 @@ impl<T> Vec2D<T> {
     }
 
     pub fn validate_index(&self, idx: Index2D) -> bool {
-        idx.column < self.row_length && idx.row < self.storage.len() / self.row_length
+        idx.column < self.row_length && idx.row.checked_mul(self.row_length).is_some_and(|start| start < self.storage.len())
     }
 
     pub fn row_length(&self) -> usize {
@@ -580,17 +672,82 @@ impl<T: Clone> Vec2D<T> {
     }
 }
 
-pub struct Slice2DVisor<'a> {
-    bytes: &'a [u8],
+/** Bounds-checked access to the cells of a grid, and to the neighbours of a cell */
+pub trait Grid2D {
+    type Cell;
+
+    fn get(&self, idx: Index2D) -> Option<&Self::Cell>;
+
+    /** Neighbours in the compass directions that lie within the grid */
+    fn neighbours4(&self, idx: Index2D) -> impl Iterator<Item = (Index2D, &Self::Cell)> {
+        CompassDirection::ALL.into_iter().filter_map(move |direction| {
+            let neighbour = idx + direction;
+            self.get(neighbour).map(|cell| (neighbour, cell))
+        })
+    }
+
+    /** Neighbours in the compass and diagonal directions that lie within the grid */
+    fn neighbours8(&self, idx: Index2D) -> impl Iterator<Item = (Index2D, &Self::Cell)> {
+        OrdinalDirection::ALL.into_iter().filter_map(move |direction| {
+            let neighbour = idx + direction;
+            self.get(neighbour).map(|cell| (neighbour, cell))
+        })
+    }
+
+    /** Cells from `start` onwards in `direction`, up to the edge of the grid */
+    fn ray(&self, start: Index2D, direction: OrdinalDirection) -> impl Iterator<Item = (Index2D, &Self::Cell)> {
+        let mut next = Some(start);
+        std::iter::from_fn(move || {
+            let idx = next?;
+            let cell = self.get(idx);
+            next = cell.map(|_| idx + direction);
+            cell.map(|cell| (idx, cell))
+        })
+    }
+}
+
+/** Grids whose cells can be modified in place */
+pub trait Grid2DMut: Grid2D {
+    fn get_mut(&mut self, idx: Index2D) -> Option<&mut Self::Cell>;
+}
+
+impl<T> Grid2D for Vec2D<T> {
+    type Cell = T;
+
+    fn get(&self, idx: Index2D) -> Option<&T> {
+        if self.validate_index(idx) {
+            Some(&self.storage[idx.row * self.row_length + idx.column])
+        } else {
+            None
+        }
+    }
+}
+
+impl<T> Grid2DMut for Vec2D<T> {
+    fn get_mut(&mut self, idx: Index2D) -> Option<&mut T> {
+        if self.validate_index(idx) {
+            Some(&mut self.storage[idx.row * self.row_length + idx.column])
+        } else {
+            None
+        }
+    }
+}
+
+/** Grid view on newline separated bytes, either borrowed (`&[u8]`) or borrowed mutably (`&mut [u8]`) */
+pub struct Slice2DVisor<Bytes> {
+    bytes: Bytes,
     newline_at: usize,
+    rows: usize,
 }
 
 static OUTSIDE: u8 = b'!';
-impl<'a> Slice2DVisor<'a> {
-    pub fn new(bytes: &'a [u8]) -> Self {
-        let newline_at = bytes.iter().position(|it| *it == b'\n').unwrap();
+impl<Bytes: AsRef<[u8]>> Slice2DVisor<Bytes> {
+    pub fn new(bytes: Bytes) -> Self {
+        let slice = bytes.as_ref();
+        let newline_at = slice.iter().position(|it| *it == b'\n').unwrap();
+        let rows = slice.len() / (newline_at + 1);
 
-        Self { bytes, newline_at }
+        Self { bytes, newline_at, rows }
     }
 
     pub fn columns(&self) -> usize {
@@ -598,27 +755,44 @@ impl<'a> Slice2DVisor<'a> {
     }
 
     pub fn rows(&self) -> usize {
-        self.bytes.len() / (self.newline_at + 1)
+        self.rows
     }
 
     pub fn at(&self, row: i64, column: i64) -> u8 {
         self[Location2D { row, column }]
     }
-}
-
-impl Index<Index2D> for Slice2DVisor<'_> {
-    type Output = u8;
 
-    fn index(&self, Index2D { row, column }: Index2D) -> &Self::Output {
+    fn offset(&self, Index2D { row, column }: Index2D) -> Option<usize> {
         if column >= self.columns() || row >= self.rows() {
-            &OUTSIDE
+            None
         } else {
-            let offset = (self.newline_at + 1) * row as usize + column as usize;
-            &self.bytes[offset]
+            Some((self.newline_at + 1) * row + column)
         }
     }
 }
-impl Index<Location2D> for Slice2DVisor<'_> {
+
+impl<Bytes: AsRef<[u8]>> Grid2D for Slice2DVisor<Bytes> {
+    type Cell = u8;
+
+    fn get(&self, idx: Index2D) -> Option<&u8> {
+        self.offset(idx).map(|offset| &self.bytes.as_ref()[offset])
+    }
+}
+
+impl<Bytes: AsRef<[u8]> + AsMut<[u8]>> Grid2DMut for Slice2DVisor<Bytes> {
+    fn get_mut(&mut self, idx: Index2D) -> Option<&mut u8> {
+        self.offset(idx).map(|offset| &mut self.bytes.as_mut()[offset])
+    }
+}
+
+impl<Bytes: AsRef<[u8]>> Index<Index2D> for Slice2DVisor<Bytes> {
+    type Output = u8;
+
+    fn index(&self, index: Index2D) -> &Self::Output {
+        self.get(index).unwrap_or(&OUTSIDE)
+    }
+}
+impl<Bytes: AsRef<[u8]>> Index<Location2D> for Slice2DVisor<Bytes> {
     type Output = u8;
 
     fn index(&self, Location2D { row, column }: Location2D) -> &Self::Output {
@@ -628,7 +802,194 @@ impl Index<Location2D> for Slice2DVisor<'_> {
             &OUTSIDE
         } else {
             let offset = (self.newline_at + 1) * row as usize + column as usize;
-            &self.bytes[offset]
+            &self.bytes.as_ref()[offset]
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::collections::{CompassDirection, Grid2D, Grid2DMut, Index2D, OrdinalDirection, Slice2DVisor, Vec2D};
+
+    fn idx(row: usize, column: usize) -> Index2D {
+        Index2D { row, column }
+    }
+
+    fn numbers() -> Vec2D<u8> {
+        Vec2D::new_from_flat(vec![1, 2, 3, 4, 5, 6], 3)
+    }
+
+    #[test]
+    fn get_on_the_first_and_last_row_and_column() {
+        let grid = numbers();
+
+        assert_eq!(Some(&1), grid.get(idx(0, 0)));
+        assert_eq!(Some(&3), grid.get(idx(0, 2)));
+        assert_eq!(Some(&4), grid.get(idx(1, 0)));
+        assert_eq!(Some(&6), grid.get(idx(1, 2)));
+    }
+
+    #[test]
+    fn get_past_the_edges() {
+        let grid = numbers();
+
+        assert_eq!(None, grid.get(idx(0, 3)));
+        assert_eq!(None, grid.get(idx(2, 0)));
+        assert_eq!(None, grid.get(idx(0, 0) + CompassDirection::NORTH));
+        assert_eq!(None, grid.get(idx(0, 0) + CompassDirection::WEST));
+        assert_eq!(None, grid.get(Index2D::IMPLAUSIBLE));
+    }
+
+    #[test]
+    fn neighbours_in_the_first_corner() {
+        let grid = numbers();
+
+        assert_eq!(vec![(idx(0, 1), &2), (idx(1, 0), &4)], grid.neighbours4(idx(0, 0)).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn neighbours_in_the_last_corner() {
+        let grid = numbers();
+
+        assert_eq!(vec![(idx(0, 2), &3), (idx(1, 1), &5)], grid.neighbours4(idx(1, 2)).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn neighbours_on_an_edge() {
+        let grid = numbers();
+
+        assert_eq!(vec![(idx(0, 2), &3), (idx(1, 1), &5), (idx(0, 0), &1)], grid.neighbours4(idx(0, 1)).collect::<Vec<_>>());
+        assert_eq!(vec![(idx(0, 0), &1), (idx(1, 1), &5)], grid.neighbours4(idx(1, 0)).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn neighbours8_in_the_corners() {
+        let grid = numbers();
+
+        assert_eq!(vec![(idx(0, 1), &2), (idx(1, 1), &5), (idx(1, 0), &4)], grid.neighbours8(idx(0, 0)).collect::<Vec<_>>());
+        assert_eq!(vec![(idx(0, 2), &3), (idx(1, 1), &5), (idx(0, 1), &2)], grid.neighbours8(idx(1, 2)).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn neighbours8_on_an_edge() {
+        let grid = numbers();
+
+        assert_eq!(
+            vec![(idx(0, 2), &3), (idx(1, 2), &6), (idx(1, 1), &5), (idx(1, 0), &4), (idx(0, 0), &1)],
+            grid.neighbours8(idx(0, 1)).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn neighbours8_on_the_visor() {
+        let visor = Slice2DVisor::new(b"abc\ndef\n");
+
+        assert_eq!(vec![(idx(0, 0), &b'a'), (idx(0, 1), &b'b'), (idx(1, 1), &b'e')], visor.neighbours8(idx(1, 0)).collect::<Vec<_>>());
+        assert_eq!(vec![(idx(1, 2), &b'f'), (idx(1, 1), &b'e'), (idx(0, 1), &b'b')], visor.neighbours8(idx(0, 2)).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn get_mut_within_and_past_the_edges() {
+        let mut grid = numbers();
+
+        *grid.get_mut(idx(1, 2)).unwrap() = 9;
+        assert_eq!(Some(&9), grid.get(idx(1, 2)));
+        assert!(grid.get_mut(idx(1, 3)).is_none());
+        assert!(grid.get_mut(idx(2, 0)).is_none());
+        assert!(grid.get_mut(Index2D::IMPLAUSIBLE).is_none());
+    }
+
+    #[test]
+    fn get_mut_on_the_visor() {
+        let mut bytes = *b"abc\ndef\n";
+        let mut visor = Slice2DVisor::new(&mut bytes[..]);
+
+        *visor.get_mut(idx(0, 1)).unwrap() = b'x';
+        assert!(visor.get_mut(idx(0, 3)).is_none());
+        assert!(visor.get_mut(idx(2, 0)).is_none());
+        assert_eq!(b"axc\ndef\n", &bytes);
+    }
+
+    #[test]
+    fn visor_skips_the_line_breaks() {
+        let visor = Slice2DVisor::new(b"abc\ndef\n");
+
+        assert_eq!(Some(&b'a'), visor.get(idx(0, 0)));
+        assert_eq!(Some(&b'f'), visor.get(idx(1, 2)));
+        assert_eq!(None, visor.get(idx(0, 3)));
+        assert_eq!(None, visor.get(idx(2, 0)));
+        assert_eq!(vec![(idx(0, 1), &b'b'), (idx(1, 0), &b'd')], visor.neighbours4(idx(0, 0)).collect::<Vec<_>>());
+        assert_eq!(vec![(idx(0, 2), &b'c'), (idx(1, 1), &b'e')], visor.neighbours4(idx(1, 2)).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn ordinal_steps_past_the_first_row_and_column() {
+        assert_eq!(Index2D::IMPLAUSIBLE, idx(0, 0) + OrdinalDirection::NORTHWEST);
+        assert_eq!(Index2D::IMPLAUSIBLE, idx(0, 1) + OrdinalDirection::NORTHEAST);
+        assert_eq!(Index2D::IMPLAUSIBLE, idx(1, 0) + OrdinalDirection::SOUTHWEST);
+        assert_eq!(Index2D::IMPLAUSIBLE, Index2D::IMPLAUSIBLE + OrdinalDirection::SOUTHEAST);
+        assert_eq!(idx(1, 1), idx(0, 0) + OrdinalDirection::SOUTHEAST);
+    }
+
+    #[test]
+    fn ordinal_steps_past_the_last_row_and_column() {
+        let grid = numbers();
+
+        assert_eq!(None, grid.get(idx(1, 2) + OrdinalDirection::SOUTHEAST));
+        assert_eq!(None, grid.get(idx(0, 2) + OrdinalDirection::EAST));
+        assert_eq!(None, grid.get(idx(1, 0) + OrdinalDirection::SOUTH));
+    }
+
+    #[test]
+    fn eight_turns_return_to_the_start() {
+        for direction in OrdinalDirection::ALL {
+            assert_eq!(direction, (0..8).fold(direction, |turned, _| turned.turn_right_45()));
+            assert_eq!(direction, (0..8).fold(direction, |turned, _| turned.turn_left_45()));
         }
     }
+
+    #[test]
+    fn left_and_right_turns_cancel() {
+        for direction in OrdinalDirection::ALL {
+            assert_eq!(direction, direction.turn_right_45().turn_left_45());
+            assert_eq!(direction, direction.turn_left_45().turn_right_45());
+        }
+        assert_eq!(OrdinalDirection::NORTHEAST, OrdinalDirection::NORTH.turn_right_45());
+        assert_eq!(OrdinalDirection::NORTHWEST, OrdinalDirection::NORTH.turn_left_45());
+    }
+
+    #[test]
+    fn visor_at_outside_the_grid() {
+        let visor = Slice2DVisor::new(b"abc\ndef\n");
+
+        assert_eq!(b'e', visor.at(1, 1));
+        assert_eq!(b'!', visor.at(-1, 0));
+        assert_eq!(b'!', visor.at(0, 3));
+    }
+
+    #[test]
+    fn ray_stops_at_the_edge() {
+        let grid = numbers();
+
+        assert_eq!(vec![(idx(0, 1), &2), (idx(0, 2), &3)], grid.ray(idx(0, 1), OrdinalDirection::EAST).collect::<Vec<_>>());
+        assert_eq!(vec![(idx(0, 2), &3), (idx(1, 1), &5)], grid.ray(idx(0, 2), OrdinalDirection::SOUTHWEST).collect::<Vec<_>>());
+        assert_eq!(vec![(idx(1, 1), &5), (idx(0, 0), &1)], grid.ray(idx(1, 1), OrdinalDirection::NORTHWEST).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn ray_from_the_edge_outwards() {
+        let grid = numbers();
+
+        assert_eq!(vec![(idx(0, 0), &1)], grid.ray(idx(0, 0), OrdinalDirection::NORTH).collect::<Vec<_>>());
+        assert_eq!(vec![(idx(1, 2), &6)], grid.ray(idx(1, 2), OrdinalDirection::SOUTHEAST).collect::<Vec<_>>());
+        assert_eq!(0, grid.ray(idx(2, 0), OrdinalDirection::NORTH).count());
+    }
+
+    #[test]
+    fn ray_across_the_visor() {
+        let visor = Slice2DVisor::new(b"abc\ndef\n");
+
+        assert_eq!(b"abc".to_vec(), visor.ray(idx(0, 0), OrdinalDirection::EAST).map(|(_, byte)| *byte).collect::<Vec<_>>());
+        assert_eq!(b"fed".to_vec(), visor.ray(idx(1, 2), OrdinalDirection::WEST).map(|(_, byte)| *byte).collect::<Vec<_>>());
+    }
 }
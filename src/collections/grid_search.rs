@@ -0,0 +1,231 @@
+use super::{Grid2D, Index2D, Vec2D};
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, VecDeque};
+
+/**
+Searches move between the tiles of a `Vec2D` in the `CompassDirection`s and return maps shaped
+like the searched grid, holding this for the tiles they never reached.
+*/
+pub const UNREACHED: usize = usize::MAX;
+
+fn unreached_map<T>(grid: &Vec2D<T>) -> Vec2D<usize> {
+    Vec2D::filled(UNREACHED, grid.rows(), grid.row_length())
+}
+
+/** Number of steps from the closest of the `starts` to every tile, moving through passable tiles only */
+pub fn distances<T>(grid: &Vec2D<T>, starts: impl IntoIterator<Item = Index2D>, passable: impl Fn(&T) -> bool) -> Vec2D<usize> {
+    let mut distances = unreached_map(grid);
+    let mut queue = VecDeque::new();
+    for start in starts {
+        distances[start] = 0;
+        queue.push_back(start);
+    }
+
+    while let Some(next) = queue.pop_front() {
+        let distance = distances[next] + 1;
+        for (neighbour, tile) in grid.neighbours4(next) {
+            if distances[neighbour] == UNREACHED && passable(tile) {
+                distances[neighbour] = distance;
+                queue.push_back(neighbour);
+            }
+        }
+    }
+
+    distances
+}
+
+/**
+Labels the tiles with the number of their component, tiles being in the same component if a chain
+of `connected` neighbours joins them. Returns the labels and the number of components.
+*/
+pub fn connected_components<T>(grid: &Vec2D<T>, connected: impl Fn(&T, &T) -> bool) -> (Vec2D<usize>, usize) {
+    let mut labels = unreached_map(grid);
+    let mut count = 0;
+    let mut stack = Vec::new();
+
+    for root in grid.indices() {
+        if labels[root] != UNREACHED {
+            continue;
+        }
+
+        labels[root] = count;
+        stack.push(root);
+        while let Some(next) = stack.pop() {
+            for (neighbour, tile) in grid.neighbours4(next) {
+                if labels[neighbour] == UNREACHED && connected(&grid[next], tile) {
+                    labels[neighbour] = count;
+                    stack.push(neighbour);
+                }
+            }
+        }
+        count += 1;
+    }
+
+    (labels, count)
+}
+
+/**
+Lowest cost from the closest of the `starts` to every state, a state being a tile in one of `LAYERS`
+layers (e.g. the facing on that tile). `moves` lists the states a state leads to, with their costs.
+*/
+pub fn dijkstra<T, const LAYERS: usize, Moves: IntoIterator<Item = ((Index2D, usize), usize)>>(
+    grid: &Vec2D<T>,
+    starts: impl IntoIterator<Item = (Index2D, usize)>,
+    moves: impl Fn(Index2D, usize) -> Moves,
+) -> Vec2D<[usize; LAYERS]> {
+    let mut costs = Vec2D::filled([UNREACHED; LAYERS], grid.rows(), grid.row_length());
+    let mut queue = BinaryHeap::new();
+    for (start, layer) in starts {
+        costs[start][layer] = 0;
+        queue.push(Reverse((0, start.row, start.column, layer)));
+    }
+
+    while let Some(Reverse((cost_so_far, row, column, layer))) = queue.pop() {
+        let next = Index2D { row, column };
+        if cost_so_far > costs[next][layer] {
+            continue;
+        }
+
+        for ((neighbour, neighbour_layer), step) in moves(next, layer) {
+            let total = cost_so_far + step;
+            if total < costs[neighbour][neighbour_layer] {
+                costs[neighbour][neighbour_layer] = total;
+                queue.push(Reverse((total, neighbour.row, neighbour.column, neighbour_layer)));
+            }
+        }
+    }
+
+    costs
+}
+
+/**
+Shortest path from `start` to `goal` through passable tiles, guided by the manhattan distance to
+the goal. Returns the tiles along the path, both ends included, and its number of steps.
+*/
+pub fn astar<T>(grid: &Vec2D<T>, start: Index2D, goal: Index2D, passable: impl Fn(&T) -> bool) -> Option<(Vec<Index2D>, usize)> {
+    let mut distances = unreached_map(grid);
+    let mut predecessors = Vec2D::filled(Index2D::IMPLAUSIBLE, grid.rows(), grid.row_length());
+    let mut queue = BinaryHeap::new();
+    distances[start] = 0;
+    // among equal estimates, the tile furthest along is expanded first
+    queue.push(Reverse((start.manhattan_distance(goal), Reverse(0), start.row, start.column)));
+
+    while let Some(Reverse((_, Reverse(distance), row, column))) = queue.pop() {
+        let next = Index2D { row, column };
+        if next == goal {
+            let mut path = vec![goal];
+            while let Some(predecessor) = path.last().map(|idx| predecessors[*idx]).filter(|idx| idx.plausible()) {
+                path.push(predecessor);
+            }
+            path.reverse();
+            return Some((path, distance));
+        }
+        if distance > distances[next] {
+            continue;
+        }
+
+        for (neighbour, tile) in grid.neighbours4(next) {
+            if distance + 1 < distances[neighbour] && passable(tile) {
+                distances[neighbour] = distance + 1;
+                predecessors[neighbour] = next;
+                let estimate = distance + 1 + neighbour.manhattan_distance(goal);
+                queue.push(Reverse((estimate, Reverse(distance + 1), neighbour.row, neighbour.column)));
+            }
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod test {
+    use crate::collections::grid_search::{astar, connected_components, dijkstra, distances, UNREACHED};
+    use crate::collections::{CompassDirection, Grid2D, Index2D, Vec2D};
+
+    const U: usize = UNREACHED;
+
+    fn grid(rows: &[&str]) -> Vec2D<u8> {
+        Vec2D::new_from_flat(rows.concat().into_bytes(), rows[0].len())
+    }
+
+    fn idx(row: usize, column: usize) -> Index2D {
+        Index2D { row, column }
+    }
+
+    #[test]
+    fn distances_around_walls() {
+        let maze = grid(&["..#", ".#.", "..."]);
+        let distances = distances(&maze, [idx(0, 0)], |tile| *tile == b'.');
+
+        assert_eq!(vec![0, 1, U, 1, U, 5, 2, 3, 4], distances.as_slice());
+    }
+
+    #[test]
+    fn distances_from_closest_start() {
+        let maze = grid(&["..#", ".#.", "..."]);
+        let distances = distances(&maze, [idx(0, 0), idx(2, 2)], |tile| *tile == b'.');
+
+        assert_eq!(vec![0, 1, U, 1, U, 1, 2, 1, 0], distances.as_slice());
+    }
+
+    #[test]
+    fn distances_leave_enclosed_tiles_unreached() {
+        let maze = grid(&[".#.", "#..", "..."]);
+        let distances = distances(&maze, [idx(0, 0)], |tile| *tile == b'.');
+
+        assert_eq!(vec![0, U, U, U, U, U, U, U, U], distances.as_slice());
+    }
+
+    #[test]
+    fn components_of_equal_neighbours() {
+        let garden = grid(&["aab", "abb", "cca"]);
+        let (labels, count) = connected_components(&garden, |a, b| a == b);
+
+        assert_eq!(4, count);
+        assert_eq!(vec![0, 0, 1, 0, 1, 1, 2, 2, 3], labels.as_slice());
+    }
+
+    #[test]
+    fn dijkstra_sums_entry_costs() {
+        let costs = grid(&["131", "191", "111"]);
+        let lowest: Vec2D<[usize; 1]> = dijkstra(&costs, [(idx(0, 0), 0)], |position, _| {
+            costs.neighbours4(position).map(|(next, cost)| ((next, 0), (cost - b'0') as usize))
+        });
+
+        assert_eq!(vec![[0], [3], [4], [1], [10], [5], [2], [3], [4]], lowest.as_slice());
+    }
+
+    #[test]
+    fn dijkstra_keeps_layers_apart() {
+        let track = grid(&["..."]);
+        // layer 0 moves east into layer 1, layer 1 returns to layer 0 in place at a higher cost
+        let lowest: Vec2D<[usize; 2]> = dijkstra(&track, [(idx(0, 0), 0)], |position, layer| {
+            let next = if layer == 0 { (position + CompassDirection::EAST, 1) } else { (position, 0) };
+            track.get(next.0).map(|_| (next, if layer == 0 { 1 } else { 10 }))
+        });
+
+        assert_eq!(vec![[0, U], [11, 1], [22, 12]], lowest.as_slice());
+    }
+
+    #[test]
+    fn astar_finds_shortest_path() {
+        let maze = grid(&["....", ".##.", "...."]);
+        let (path, steps) = astar(&maze, idx(0, 0), idx(2, 3), |tile| *tile == b'.').unwrap();
+
+        assert_eq!(5, steps);
+        assert_eq!(steps + 1, path.len());
+        assert_eq!(Some(&idx(0, 0)), path.first());
+        assert_eq!(Some(&idx(2, 3)), path.last());
+        for step in path.windows(2) {
+            assert_eq!(1, step[0].manhattan_distance(step[1]));
+            assert_eq!(b'.', maze[step[1]]);
+        }
+    }
+
+    #[test]
+    fn astar_without_path() {
+        let maze = grid(&["..#", ".##", "#.."]);
+
+        assert_eq!(None, astar(&maze, idx(0, 0), idx(2, 2), |tile| *tile == b'.'));
+    }
+}
@@ -0,0 +1,76 @@
+use super::{CompassDirection, Index2D, Vec2D};
+use crate::ui::UIWrite;
+use std::fmt::{Display, Formatter, Write};
+
+/** Text picture of a grid, one line per row, with overlays drawn over the cells */
+pub struct Rendering<'a, T, Cell> {
+    grid: &'a Vec2D<T>,
+    cell: Cell,
+    overlay: Vec2D<Option<char>>,
+}
+
+impl<T> Vec2D<T> {
+    /** Renders every cell as the char `cell` maps it to */
+    pub fn render<Cell: Fn(&T) -> char>(&self, cell: Cell) -> Rendering<'_, T, Cell> {
+        Rendering {
+            grid: self,
+            cell,
+            overlay: Vec2D::filled(None, self.rows(), self.row_length()),
+        }
+    }
+}
+
+impl CompassDirection {
+    pub fn arrow(self) -> char {
+        match self {
+            CompassDirection::NORTH => '^',
+            CompassDirection::EAST => '>',
+            CompassDirection::SOUTH => 'v',
+            CompassDirection::WEST => '<',
+        }
+    }
+}
+
+impl<T, Cell: Fn(&T) -> char> Rendering<'_, T, Cell> {
+    /** Draws `mark` at the positions, over the cells and any earlier overlay */
+    pub fn mark(mut self, positions: impl IntoIterator<Item = Index2D>, mark: char) -> Self {
+        for position in positions {
+            if self.overlay.validate_index(position) {
+                self.overlay[position] = Some(mark);
+            }
+        }
+        self
+    }
+
+    /** Draws the steps of a path as arrows pointing the way they went */
+    pub fn path(mut self, steps: impl IntoIterator<Item = (Index2D, CompassDirection)>) -> Self {
+        for (position, direction) in steps {
+            if self.overlay.validate_index(position) {
+                self.overlay[position] = Some(direction.arrow());
+            }
+        }
+        self
+    }
+
+    /** Reports the rendering as info, on the lines following the caption */
+    pub fn write_to(&self, out: &mut impl UIWrite, caption: &str) {
+        out.info(format_args!("{caption}:\n{self}"));
+    }
+}
+
+impl<T, Cell: Fn(&T) -> char> Display for Rendering<'_, T, Cell> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for row in 0..self.grid.rows() {
+            if row > 0 {
+                f.write_char('\n')?;
+            }
+            for column in 0..self.grid.row_length() {
+                let position = Index2D { row, column };
+                let char = self.overlay[position].unwrap_or_else(|| (self.cell)(&self.grid[position]));
+                f.write_char(char)?;
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -1,14 +1,19 @@
-use crate::collections::Index2D;
+use crate::collections::{Index2D, Vec2D};
+use crate::parameters::{with_parameters, ParameterSpec, Parameters};
 use crate::timed::time_span;
 use crate::ui::UIWrite;
+use clap::ValueEnum;
 use nom::character::complete::line_ending;
 use nom::combinator::all_consuming;
 use nom::multi::many0;
 use nom::sequence::terminated;
 use nom::IResult;
+use std::any::Any;
 use std::error::Error;
 use std::fmt::{Display, Formatter};
-use std::io::{stdin, Write};
+use std::io::Write;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::time::Duration;
 
 mod day01;
 mod day02;
@@ -37,8 +42,168 @@ mod day24;
 mod day25;
 
 pub struct Day<T: Write> {
-    pub terse: fn(&[u8], &mut T),
-    pub verbose: fn(&[u8], &mut T),
+    pub terse: fn(&[u8], Part, &mut T) -> DayResult,
+    pub verbose: fn(&[u8], Part, &mut T) -> DayResult,
+}
+
+/** Selection of the parts to run */
+#[derive(Debug, Copy, Clone, Eq, PartialEq, ValueEnum)]
+pub enum Part {
+    #[value(name = "1")]
+    One,
+    #[value(name = "2")]
+    Two,
+    Both,
+}
+
+impl Part {
+    pub fn includes_part1(self) -> bool {
+        self != Part::Two
+    }
+
+    pub fn includes_part2(self) -> bool {
+        self != Part::One
+    }
+}
+
+/** Answers reported by a day handler, one per part */
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct Answers {
+    pub part1: Option<String>,
+    pub part2: Option<String>,
+}
+
+impl Answers {
+    /** Drops the answers of the parts not selected */
+    pub fn only(self, part: Part) -> Self {
+        Self {
+            part1: self.part1.filter(|_| part.includes_part1()),
+            part2: self.part2.filter(|_| part.includes_part2()),
+        }
+    }
+}
+
+/**
+Time spent in the phases of a day handler. Handlers that cannot separate their phases
+(streaming parsers, or solvers computing both parts at once) report a combined timing instead
+*/
+#[derive(Debug, Clone, Default)]
+pub struct Timings {
+    pub parse: Option<Duration>,
+    pub part1: Option<Duration>,
+    pub part2: Option<Duration>,
+    pub combined: Option<Duration>,
+}
+
+/** Structured result of a day handler, rendered uniformly by every frontend */
+#[derive(Debug, Clone, Default)]
+pub struct DayResult {
+    pub answers: Answers,
+    pub timings: Timings,
+    pub unconsumed_bytes: usize,
+    pub error: Option<String>,
+}
+
+impl Display for Timings {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let phases = [
+            ("parse", self.parse),
+            ("part1", self.part1),
+            ("part2", self.part2),
+            ("combined", self.combined),
+        ];
+        let mut separator = "";
+        for (name, timing) in phases {
+            if let Some(timing) = timing {
+                f.write_fmt(format_args!("{separator}{name}={timing:?}"))?;
+                separator = ", ";
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Display for DayResult {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if let Some(error) = &self.error {
+            return f.write_fmt(format_args!("ERROR: {error}"));
+        }
+
+        let Answers { part1, part2 } = &self.answers;
+        f.write_fmt(format_args!(
+            "Part1: {}, Part2: {} (timings: {}",
+            part1.as_deref().unwrap_or("---"),
+            part2.as_deref().unwrap_or("---"),
+            self.timings
+        ))?;
+        if self.unconsumed_bytes > 0 {
+            f.write_fmt(format_args!("; {} unconsumed bytes", self.unconsumed_bytes))?;
+        }
+        f.write_str(")")
+    }
+}
+
+/** Conversion of the value a part solver returns into the answer reported for it */
+pub trait PartAnswer {
+    fn into_answer(self) -> Option<String>;
+}
+
+impl<T: Display> PartAnswer for T {
+    fn into_answer(self) -> Option<String> {
+        Some(self.to_string())
+    }
+}
+
+/** Placeholder result for parts without a solver */
+pub struct Unsolved;
+
+impl PartAnswer for Unsolved {
+    fn into_answer(self) -> Option<String> {
+        None
+    }
+}
+
+/** Answer of a part that has no solution for some inputs */
+pub struct OptionalAnswer<T>(pub Option<T>);
+
+impl<T: Display> PartAnswer for OptionalAnswer<T> {
+    fn into_answer(self) -> Option<String> {
+        self.0.map(|answer| answer.to_string())
+    }
+}
+
+impl<T: Write> Day<T> {
+    /**
+    Runs the terse or verbose handler on the given input, with the day's puzzle parameters in
+    effect. A panicking handler is reported as failed day, so that the remaining days can still run.
+    */
+    pub fn execute(&self, day: u8, verbose: bool, part: Part, parameters: &Parameters, input: &[u8], output: &mut T) -> DayResult {
+        let handler = if verbose { self.verbose } else { self.terse };
+        let run = || with_parameters(parameters.clone(), || handler(input, part, output));
+        match catch_unwind(AssertUnwindSafe(run)) {
+            Ok(result) => result,
+            Err(payload) => {
+                let error = format!("panicked: {}", panic_message(payload.as_ref()));
+                _ = writeln!(output, "day {day}: {error}");
+                _ = output.flush();
+                DayResult {
+                    error: Some(error),
+                    ..DayResult::default()
+                }
+            }
+        }
+    }
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> &str {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message
+    } else {
+        "unknown cause"
+    }
 }
 
 impl<T: Write> Clone for Day<T> {
@@ -59,69 +224,64 @@ macro_rules! unimplemented_day {
     };
 }
 
-fn visual_inspection(to_verify: impl Display, solution: usize) -> bool {
-    if cfg!(debug_assertions) {
-        println!("{}", to_verify);
-        println!("Accept {solution}?");
-        let mut response = String::new();
-        stdin().read_line(&mut response).unwrap();
-        response.starts_with("y")
-    } else {
-        true
-    }
-}
-
 pub fn parse_and_execute_stream<
     'input,
-    'output,
     ParseArtifact: 'input,
     State: Default,
     Parse: FnMut(&'input [u8]) -> IResult<&'input [u8], ParseArtifact, nom::error::Error<&'input [u8]>>,
     Handler: Fn(&mut State, ParseArtifact),
-    Formatter: FnOnce(State) -> Output,
-    Output: Display,
+    Formatter: FnOnce(State) -> (Result1, Result2),
+    Result1: PartAnswer,
+    Result2: PartAnswer,
 >(
     mut parse: Parse,
     handler: Handler,
     format: Formatter,
+    part: Part,
     mut input: &'input [u8],
-) -> String {
-    let mut next = parse(input);
-    let mut state = State::default();
-    while let Ok((rest, next_element)) = next {
-        handler(&mut state, next_element);
-        input = rest;
-        next = parse(input)
-    }
+) -> DayResult {
+    let ((part1, part2), combined_time) = time_span(|| {
+        let mut next = parse(input);
+        let mut state = State::default();
+        while let Ok((rest, next_element)) = next {
+            handler(&mut state, next_element);
+            input = rest;
+            next = parse(input)
+        }
+
+        format(state)
+    });
 
-    format!("{} ({} unconsumed bytes)", format(state), input.len())
+    DayResult {
+        answers: Answers {
+            part1: part1.into_answer(),
+            part2: part2.into_answer(),
+        }.only(part),
+        timings: Timings {
+            combined: Some(combined_time),
+            ..Timings::default()
+        },
+        unconsumed_bytes: input.len(),
+        error: None,
+    }
 }
 
-pub fn parse_and_execute<
+fn parse_timed<
     'input,
-    'output,
     Parse: FnOnce(&'input [u8]) -> Result<ParseArtifact, ParseError>,
-    Part1: FnOnce(&mut ParseArtifact) -> Result1,
-    Part2: FnOnce(ParseArtifact) -> Result2,
     ParseArtifact: 'input,
     ParseError: Error + 'input,
-    Result1: Display,
-    Result2: Display,
     UI: UIWrite,
 >(
     parse: Parse,
-    part1: Part1,
-    part2: Part2,
     input: &'input [u8],
-    output: &'output mut UI,
-) -> String {
+    output: &mut UI,
+) -> Result<(ParseArtifact, Duration), DayResult> {
     let (parsed, parse_time) = time_span(|| parse(input));
     match parsed {
-        Ok(mut parsed) => {
+        Ok(parsed) => {
             output.info(format_args!("Parsed input successfully"));
-            let (part1, part1_time) = time_span(|| part1(&mut parsed));
-            let (part2, part2_time) = time_span(|| part2(parsed));
-            format!("Part1: {part1}, Part2: {part2} (timings: parse={parse_time:?}, part1={part1_time:?}, part2={part2_time:?})")
+            Ok((parsed, parse_time))
         }
         Err(failed) => {
             output.critical(format_args!(
@@ -129,11 +289,218 @@ pub fn parse_and_execute<
                 String::from_utf8_lossy(input),
                 failed
             ));
-            format!("ERROR: {}", failed)
+            Err(DayResult {
+                timings: Timings {
+                    parse: Some(parse_time),
+                    ..Timings::default()
+                },
+                error: Some(failed.to_string()),
+                ..DayResult::default()
+            })
         }
     }
 }
 
+pub fn parse_and_execute<
+    'input,
+    'output,
+    Parse: FnOnce(&'input [u8]) -> Result<ParseArtifact, ParseError>,
+    Part1: FnOnce(&mut ParseArtifact) -> Result1,
+    Part2: FnOnce(ParseArtifact) -> Result2,
+    ParseArtifact: 'input,
+    ParseError: Error + 'input,
+    Result1: PartAnswer,
+    Result2: PartAnswer,
+    UI: UIWrite,
+>(
+    parse: Parse,
+    part1: Part1,
+    part2: Part2,
+    part: Part,
+    input: &'input [u8],
+    output: &'output mut UI,
+) -> DayResult {
+    parse_and_execute_reporting(parse, |parsed, _: &mut UI| part1(parsed), |parsed, _: &mut UI| part2(parsed), part, input, output)
+}
+
+/** Like `parse_and_execute`, for days whose part 2 continues from the state part 1 left behind */
+pub fn parse_and_execute_chained<
+    'input,
+    'output,
+    Parse: FnOnce(&'input [u8]) -> Result<ParseArtifact, ParseError>,
+    Part1: FnOnce(&mut ParseArtifact) -> Result1,
+    Part2: FnOnce(ParseArtifact) -> Result2,
+    ParseArtifact: 'input,
+    ParseError: Error + 'input,
+    Result1: PartAnswer,
+    Result2: PartAnswer,
+    UI: UIWrite,
+>(
+    parse: Parse,
+    part1: Part1,
+    part2: Part2,
+    part: Part,
+    input: &'input [u8],
+    output: &'output mut UI,
+) -> DayResult {
+    if part.includes_part1() {
+        parse_and_execute(parse, part1, part2, part, input, output)
+    } else {
+        // part 1 still has to prepare the state, but its time counts towards part 2
+        parse_and_execute(parse, |_| Unsolved, |mut parsed| {
+            part1(&mut parsed);
+            part2(parsed)
+        }, part, input, output)
+    }
+}
+
+/** Like `parse_and_execute`, with both parts reporting their progress to the UI */
+pub fn parse_and_execute_reporting<
+    'input,
+    'output,
+    Parse: FnOnce(&'input [u8]) -> Result<ParseArtifact, ParseError>,
+    Part1: FnOnce(&mut ParseArtifact, &mut UI) -> Result1,
+    Part2: FnOnce(ParseArtifact, &mut UI) -> Result2,
+    ParseArtifact: 'input,
+    ParseError: Error + 'input,
+    Result1: PartAnswer,
+    Result2: PartAnswer,
+    UI: UIWrite,
+>(
+    parse: Parse,
+    part1: Part1,
+    part2: Part2,
+    part: Part,
+    input: &'input [u8],
+    output: &'output mut UI,
+) -> DayResult {
+    let (mut parsed, parse_time) = match parse_timed(parse, input, output) {
+        Ok(parsed) => parsed,
+        Err(failed) => return failed,
+    };
+    let (part1, part1_time) = if part.includes_part1() {
+        time_span(|| part1(&mut parsed, output).into_answer())
+    } else {
+        (None, Duration::ZERO)
+    };
+    let (part2, part2_time) = if part.includes_part2() {
+        time_span(|| part2(parsed, output).into_answer())
+    } else {
+        (None, Duration::ZERO)
+    };
+
+    DayResult {
+        timings: Timings {
+            parse: Some(parse_time),
+            part1: part1.as_ref().map(|_| part1_time),
+            part2: part2.as_ref().map(|_| part2_time),
+            combined: None,
+        },
+        answers: Answers { part1, part2 },
+        unconsumed_bytes: 0,
+        error: None,
+    }
+}
+
+/**
+Like `parse_and_execute`, followed by `export` writing files derived from the run (renderings,
+animation frames). The export is not timed; it gets the parsed input and the answer of part 2
+if that ran, and a failing export is reported as the error of the day.
+*/
+pub fn parse_and_execute_exporting<
+    'input,
+    'output,
+    Parse: FnOnce(&'input [u8]) -> Result<ParseArtifact, ParseError>,
+    Part1: FnOnce(&mut ParseArtifact) -> Result1,
+    Part2: FnOnce(&ParseArtifact) -> Result2,
+    Export: FnOnce(&ParseArtifact, Option<&Result2>, &mut UI) -> Result<(), String>,
+    ParseArtifact: 'input,
+    ParseError: Error + 'input,
+    Result1: PartAnswer,
+    Result2: PartAnswer,
+    UI: UIWrite,
+>(
+    parse: Parse,
+    part1: Part1,
+    part2: Part2,
+    export: Export,
+    part: Part,
+    input: &'input [u8],
+    output: &'output mut UI,
+) -> DayResult {
+    let (mut parsed, parse_time) = match parse_timed(parse, input, output) {
+        Ok(parsed) => parsed,
+        Err(failed) => return failed,
+    };
+    let (part1, part1_time) = if part.includes_part1() {
+        time_span(|| part1(&mut parsed).into_answer())
+    } else {
+        (None, Duration::ZERO)
+    };
+    let (part2, part2_time) = if part.includes_part2() {
+        let (part2, part2_time) = time_span(|| part2(&parsed));
+        (Some(part2), part2_time)
+    } else {
+        (None, Duration::ZERO)
+    };
+
+    let error = export(&parsed, part2.as_ref(), output).err();
+    if let Some(error) = &error {
+        output.critical(format_args!("{error}"));
+    }
+
+    let part2 = part2.and_then(PartAnswer::into_answer);
+    DayResult {
+        timings: Timings {
+            parse: Some(parse_time),
+            part1: part1.as_ref().map(|_| part1_time),
+            part2: part2.as_ref().map(|_| part2_time),
+            combined: None,
+        },
+        answers: Answers { part1, part2 },
+        unconsumed_bytes: 0,
+        error,
+    }
+}
+
+pub fn parse_and_execute_combined<
+    'input,
+    'output,
+    Parse: FnOnce(&'input [u8]) -> Result<ParseArtifact, ParseError>,
+    Solve: FnOnce(ParseArtifact, Part, &mut UI) -> (Result1, Result2),
+    ParseArtifact: 'input,
+    ParseError: Error + 'input,
+    Result1: PartAnswer,
+    Result2: PartAnswer,
+    UI: UIWrite,
+>(
+    parse: Parse,
+    solve: Solve,
+    part: Part,
+    input: &'input [u8],
+    output: &'output mut UI,
+) -> DayResult {
+    let (parsed, parse_time) = match parse_timed(parse, input, output) {
+        Ok(parsed) => parsed,
+        Err(failed) => return failed,
+    };
+    let ((part1, part2), combined_time) = time_span(|| solve(parsed, part, output));
+
+    DayResult {
+        answers: Answers {
+            part1: part1.into_answer(),
+            part2: part2.into_answer(),
+        }.only(part),
+        timings: Timings {
+            parse: Some(parse_time),
+            combined: Some(combined_time),
+            ..Timings::default()
+        },
+        unconsumed_bytes: 0,
+        error: None,
+    }
+}
+
 #[derive(Debug)]
 struct SimpleError(String);
 
@@ -175,6 +542,86 @@ pub fn parse_graphical_input(input: &[u8], mut handler: impl FnMut(u8, Index2D))
     latest
 }
 
+/** Why grid input could not be parsed. Lines and columns count from 1, as editors show them */
+#[derive(Debug)]
+pub enum GridError {
+    Empty,
+    UnexpectedByte { line: usize, column: usize, byte: u8 },
+    RaggedLine { line: usize, length: usize, expected: usize },
+    MissingMarker(u8),
+}
+
+impl Display for GridError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GridError::Empty => f.write_str("Grid input is empty"),
+            GridError::UnexpectedByte { line, column, byte } =>
+                f.write_fmt(format_args!("Unexpected {:?} at line {line}, column {column}", char::from(*byte))),
+            GridError::RaggedLine { line, length, expected } =>
+                f.write_fmt(format_args!("Line {line} has {length} columns, but the lines before have {expected}")),
+            GridError::MissingMarker(marker) =>
+                f.write_fmt(format_args!("No {:?} marker in the grid", char::from(*marker))),
+        }
+    }
+}
+
+impl Error for GridError {}
+
+impl From<GridError> for SimpleError {
+    fn from(error: GridError) -> Self {
+        SimpleError(error.to_string())
+    }
+}
+
+/** A parsed grid, and where in it the marker bytes were found */
+pub struct ParsedGrid<T> {
+    pub grid: Vec2D<T>,
+    markers: Vec<(u8, Index2D)>,
+}
+
+impl<T> ParsedGrid<T> {
+    /** Position of the first occurrence of `marker` */
+    pub fn marker(&self, marker: u8) -> Result<Index2D, GridError> {
+        self.markers.iter()
+            .find(|(byte, _)| *byte == marker)
+            .map(|(_, position)| *position)
+            .ok_or(GridError::MissingMarker(marker))
+    }
+}
+
+/**
+Parses a rectangular grid, one line per row, mapping each byte to a cell. Bytes the mapping
+rejects are errors, as are lines of differing length. Line ends may be LF or CRLF, and trailing
+empty lines are ignored. The positions of the bytes in `markers` (like `S` or `^`) are recorded
+as well, their cells still coming from the mapping.
+*/
+pub fn parse_grid<T>(input: &[u8], markers: &[u8], mut cell: impl FnMut(u8) -> Option<T>) -> Result<ParsedGrid<T>, GridError> {
+    let lines: Vec<_> = input
+        .split(|byte| *byte == b'\n')
+        .map(|line| line.strip_suffix(b"\r").unwrap_or(line))
+        .collect();
+    let line_count = lines.iter().rposition(|line| !line.is_empty()).map_or(0, |last| last + 1);
+    let Some(expected) = lines.first().map(|line| line.len()).filter(|length| *length > 0) else {
+        return Err(GridError::Empty);
+    };
+
+    let mut cells = Vec::with_capacity(line_count * expected);
+    let mut found = Vec::new();
+    for (row, line) in lines[..line_count].iter().enumerate() {
+        if line.len() != expected {
+            return Err(GridError::RaggedLine { line: row + 1, length: line.len(), expected });
+        }
+        for (column, byte) in line.iter().copied().enumerate() {
+            if markers.contains(&byte) {
+                found.push((byte, Index2D { row, column }));
+            }
+            cells.push(cell(byte).ok_or(GridError::UnexpectedByte { line: row + 1, column: column + 1, byte })?);
+        }
+    }
+
+    Ok(ParsedGrid { grid: Vec2D::new_from_flat(cells, expected), markers: found })
+}
+
 const fn nom_parsed_bytes<
     'i,
     ParseResult: 'i,
@@ -200,8 +647,8 @@ macro_rules! streaming_day {
         streaming_day!($parse, $handle, |x| x);
     };
     ($parse:expr, $handle:expr, $format:expr) => {
-        simple_day! {|x|
-           crate::day::parse_and_execute_stream($parse, $handle, $format, x)
+        simple_day! {|x, part, _out|
+           crate::day::parse_and_execute_stream($parse, $handle, $format, part, x)
         }
     };
 }
@@ -214,10 +661,45 @@ macro_rules! parsed_day {
         ));
     };
     ($parse:expr, $part1:expr) => {
-        parsed_day!($parse, |_| "---", $part1);
+        combined_day!($parse, |parsed, _| ($part1(parsed), crate::day::Unsolved));
+    };
+    ($parse:expr, $part1:expr, $part2:expr) => {
+        simple_day!(|i, part, o| crate::day::parse_and_execute($parse, $part1, $part2, part, i, &mut o));
     };
+}
+
+/** Like `parsed_day!`, for days whose part 2 continues from the state part 1 left behind */
+#[macro_export]
+macro_rules! chained_day {
     ($parse:expr, $part1:expr, $part2:expr) => {
-        simple_day!(|i, o| crate::day::parse_and_execute($parse, $part1, $part2, i, &mut o));
+        simple_day!(|i, part, o| crate::day::parse_and_execute_chained($parse, $part1, $part2, part, i, &mut o));
+    };
+}
+
+/** Like `parsed_day!`, with an untimed export after the parts, see `parse_and_execute_exporting` */
+#[macro_export]
+macro_rules! exporting_day {
+    ($parse:expr, $part1:expr, $part2:expr, $export:expr) => {
+        simple_day!(|i, part, o| crate::day::parse_and_execute_exporting($parse, $part1, $part2, $export, part, i, &mut o));
+    };
+}
+
+/** Day solving both parts at once, the solver receiving the selected part to skip the work for the other */
+#[macro_export]
+macro_rules! combined_day {
+    ($parse:expr, $solve:expr) => {
+        reporting_day!($parse, |parsed, part, _| $solve(parsed, part));
+    };
+}
+
+/** Like `combined_day!`, with the solver also receiving the UI to report its progress on */
+#[macro_export]
+macro_rules! reporting_day {
+    ($parse:expr, $solve:expr) => {
+        simple_day!(|i, part, o| crate::day::parse_and_execute_combined($parse, $solve, part, i, &mut o));
+    };
+    ($parse:expr, $part1:expr, $part2:expr) => {
+        simple_day!(|i, part, o| crate::day::parse_and_execute_reporting($parse, $part1, $part2, part, i, &mut o));
     };
 }
 
@@ -230,17 +712,22 @@ macro_rules! simple_day {
         simple_day! { |$n, _out| $body }
     };
     (| $name:ident, $out:ident | $body:expr ) => {
+        simple_day! { |$name, _part, $out| $body }
+    };
+    (| $name:ident, $part:ident, $out:ident | $body:expr ) => {
         pub const fn register<T: std::io::Write>() -> Option<crate::day::Day<T>> {
             fn solve_trampoline<T: std::io::Write, UI: crate::ui::UIFactory>(
                 $name: &[u8],
+                $part: crate::day::Part,
                 writer: &mut T,
-            ) {
+            ) -> crate::day::DayResult {
                 use crate::ui::UIWrite;
                 let mut $out = UI::create(writer, module_path!());
 
                 $out.info(format_args!("Started"));
-                let result = $body;
+                let result: crate::day::DayResult = $body;
                 $out.result(format_args!("{result}"));
+                result
             }
             Some(crate::day::Day {
                 terse: solve_trampoline::<T, crate::ui::Terse>,
@@ -250,6 +737,18 @@ macro_rules! simple_day {
     };
 }
 
+/** Puzzle parameters a day looks up, to check the overrides given on startup against */
+pub fn declared_parameters(day: u8) -> &'static [ParameterSpec] {
+    match day {
+        11 => day11::PARAMETERS,
+        14 => day14::PARAMETERS,
+        18 => day18::PARAMETERS,
+        20 => day20::PARAMETERS,
+        24 => day24::PARAMETERS,
+        _ => &[],
+    }
+}
+
 pub const fn handlers<T: Write>() -> [Option<Day<T>>; 25] {
     [
         day01::register::<T>(),
@@ -279,3 +778,65 @@ pub const fn handlers<T: Write>() -> [Option<Day<T>>; 25] {
         day25::register::<T>(),
     ]
 }
+
+#[cfg(test)]
+mod test {
+    use crate::collections::Index2D;
+    use crate::day::{parse_grid, GridError};
+
+    fn walls(byte: u8) -> Option<bool> {
+        match byte {
+            b'#' => Some(true),
+            b'.' | b'S' | b'E' => Some(false),
+            _ => None
+        }
+    }
+
+    #[test]
+    fn parses_cells_row_by_row() {
+        let parsed = parse_grid(b"#.#\n..#\n", b"", walls).unwrap();
+
+        assert_eq!(parsed.grid.row_length(), 3);
+        assert_eq!(parsed.grid.as_slice(), &[true, false, true, false, false, true]);
+    }
+
+    #[test]
+    fn accepts_crlf_and_trailing_blank_lines() {
+        let parsed = parse_grid(b"#.\r\n.#\r\n\r\n\n", b"", walls).unwrap();
+
+        assert_eq!(parsed.grid.rows(), 2);
+        assert_eq!(parsed.grid.as_slice(), &[true, false, false, true]);
+    }
+
+    #[test]
+    fn records_markers() {
+        let parsed = parse_grid(b"S.#\n#.E\n", b"SE", walls).unwrap();
+
+        assert_eq!(parsed.marker(b'S').unwrap(), Index2D { row: 0, column: 0 });
+        assert_eq!(parsed.marker(b'E').unwrap(), Index2D { row: 1, column: 2 });
+        assert_eq!(parsed.grid.as_slice(), &[false, false, true, true, false, false]);
+        assert!(matches!(parsed.marker(b'^'), Err(GridError::MissingMarker(b'^'))));
+    }
+
+    #[test]
+    fn reports_ragged_line() {
+        let error = parse_grid(b"#.#\n..#\n.#\n", b"", walls).err().unwrap();
+
+        assert!(matches!(error, GridError::RaggedLine { line: 3, length: 2, expected: 3 }));
+        assert_eq!(error.to_string(), "Line 3 has 2 columns, but the lines before have 3");
+    }
+
+    #[test]
+    fn reports_unexpected_byte() {
+        let error = parse_grid(b"#.#\n.x#\n", b"", walls).err().unwrap();
+
+        assert!(matches!(error, GridError::UnexpectedByte { line: 2, column: 2, byte: b'x' }));
+        assert_eq!(error.to_string(), "Unexpected 'x' at line 2, column 2");
+    }
+
+    #[test]
+    fn reports_empty_input() {
+        assert!(matches!(parse_grid(b"", b"", walls), Err(GridError::Empty)));
+        assert!(matches!(parse_grid(b"\n\n", b"", walls), Err(GridError::Empty)));
+    }
+}
@@ -7,7 +7,7 @@ struct Day2 {
     clean: usize,
 }
 
-parsed_day!(
+chained_day!(
     |input|{
         let mut candidates = Vec::new();
 
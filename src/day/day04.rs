@@ -1,105 +1,38 @@
 use crate::*;
-use crate::collections::Slice2DVisor;
+use crate::collections::{Grid2D, Index2D, OrdinalDirection, Slice2DVisor};
 
-const XMAS: &'static [u8;4] = b"XMAS";
-const SAMX: &'static [u8;4] = b"SAMX";
+const XMAS: &'static [u8; 4] = b"XMAS";
+const MAS: &'static [u8; 3] = b"MAS";
 
-const X_MAS_PATTERNS: [&'static [u8; 5]; 4] = [
-    /* M S
-        A
-       M S 
-     */
-    b"AMSMS",
-    /* M M
-        A
-       S S 
-     */
-    b"AMMSS",
-    /* S M
-        A
-       S M 
-     */
-    b"ASMSM",
-    /* S S
-        A
-       M M 
-     */
-    b"ASSMM",
-];
-fn solve_1(visor: &Slice2DVisor) -> usize {
-    let mut bytes = [0u8; 4];
-    let mut found = 0;
-
-    for row in 0..visor.rows() as i64 {
-        for column in 0..visor.columns() as i64 {
-            bytes[0] = visor.at(row, column);
-            if bytes[0] != b'X' && bytes[0] != b'S' {
-                continue
-            }
-
-            bytes[1] = visor.at(row, column + 1);
-            bytes[2] = visor.at(row, column + 2);
-            bytes[3] = visor.at(row, column + 3);
-
-            if &bytes == XMAS || &bytes == SAMX {
-                found += 1;
-            }
-
-            bytes[1] = visor.at(row + 1, column + 1);
-            bytes[2] = visor.at(row + 2, column + 2);
-            bytes[3] = visor.at(row + 3, column + 3);
-
-            if &bytes == XMAS || &bytes == SAMX {
-                found += 1;
-            }
-
-
-            bytes[1] = visor.at(row + 1, column);
-            bytes[2] = visor.at(row + 2, column);
-            bytes[3] = visor.at(row + 3, column);
-
-            if &bytes == XMAS || &bytes == SAMX {
-                found += 1;
-            }
-
-            bytes[1] = visor.at(row + 1, column - 1);
-            bytes[2] = visor.at(row + 2, column - 2);
-            bytes[3] = visor.at(row + 3, column - 3);
-
-            if &bytes == XMAS || &bytes == SAMX {
-                found += 1;
-            }
-        }
-    }
-
-    found
+fn indices(visor: &Slice2DVisor<&[u8]>) -> impl Iterator<Item = Index2D> {
+    let columns = visor.columns();
+    (0..visor.rows()).flat_map(move |row| (0..columns).map(move |column| Index2D { row, column }))
 }
 
-fn solve_2(visor: Slice2DVisor) -> usize {
-    let mut bytes = [0u8; 5];
-    let mut found = 0;
-
-    for row in 0..visor.rows() as i64 {
-        'col: for column in 0..visor.columns() as i64 {
-            bytes[0] = visor.at(row + 1, column + 1);
-            if bytes[0] != b'A'  {
-                continue
-            }
+/** Whether `word` can be read from `start` onwards in `direction` */
+fn spells(visor: &Slice2DVisor<&[u8]>, start: Index2D, direction: OrdinalDirection, word: &[u8]) -> bool {
+    let mut ray = visor.ray(start, direction);
+    word.iter().all(|expected| ray.next().is_some_and(|(_, byte)| byte == expected))
+}
 
-            bytes[1] = visor.at(row, column);
-            bytes[2] = visor.at(row, column + 2);
-            bytes[3] = visor.at(row + 2, column);
-            bytes[4] = visor.at(row + 2, column + 2);
-            for pattern in X_MAS_PATTERNS {
-                if &bytes == pattern {
-                    found += 1;
-                    continue 'col;
-                }
-            }
-        }
-    }
+fn solve_1(visor: &Slice2DVisor<&[u8]>) -> usize {
+    indices(visor)
+        .filter(|idx| visor[*idx] == XMAS[0])
+        .map(|idx| OrdinalDirection::ALL.into_iter().filter(|direction| spells(visor, idx, *direction, XMAS)).count())
+        .sum()
+}
 
-    found
+/** An X-MAS is an A with MAS written across both of its diagonals, in either direction */
+fn solve_2(visor: Slice2DVisor<&[u8]>) -> usize {
+    let crossing_mas = |center: Index2D, diagonal: OrdinalDirection| {
+        [diagonal, diagonal.opposite()].into_iter()
+            .any(|direction| spells(&visor, center + direction.opposite(), direction, MAS))
+    };
+
+    indices(&visor)
+        .filter(|idx| visor[*idx] == MAS[1])
+        .filter(|idx| crossing_mas(*idx, OrdinalDirection::NORTHEAST) && crossing_mas(*idx, OrdinalDirection::SOUTHEAST))
+        .count()
 }
 
-parsed_day!(|str|Ok::<_, !>(Slice2DVisor::new(str)), |i|solve_1(i), solve_2);
\ No newline at end of file
+parsed_day!(|str|Ok::<_, !>(Slice2DVisor::new(str)), |i|solve_1(i), solve_2);
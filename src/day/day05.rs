@@ -7,7 +7,7 @@ use nom::multi::{many1, separated_list1};
 use nom::sequence::{separated_pair, terminated};
 use crate::*;
 use crate::collections::{ArrayBag, IndexMap};
-use crate::day::nom_parsed_bytes;
+use crate::day::{nom_parsed_bytes, Part};
 use crate::parse_helpers::parse_unsigned_nr_bytes;
 use crate::worker::parallelize;
 
@@ -60,7 +60,7 @@ fn build_token_ordering(tokens: &[u8], rules: &[Constraint]) -> TokenOrdering {
     TokenOrdering { lookup_positions }
 }
 
-fn part1((constraints, token_lists):  (Vec<Constraint>, Vec<Vec<u8>>)) -> String {
+fn solve((constraints, token_lists):  (Vec<Constraint>, Vec<Vec<u8>>), part: Part) -> (i32, i32) {
     let mut sum_1 = 0i32;
     let mut sum_2 = 0i32;
 
@@ -70,6 +70,11 @@ fn part1((constraints, token_lists):  (Vec<Constraint>, Vec<Vec<u8>>)) -> String
         let constraints = constraints.clone();
         move ||{
             let constraint = build_token_ordering(&l, &constraints);
+            let in_order = |l: &u8, r: &u8| constraint.lookup_positions[*l as usize] <= constraint.lookup_positions[*r as usize];
+            if !part.includes_part2() && !l.is_sorted_by(in_order) {
+                // only the correctly ordered lists count, no need to fix this one
+                return 0;
+            }
             let mut clone = l.clone();
             clone.sort_by(|l, r| constraint.lookup_positions[*l as usize].cmp(&constraint.lookup_positions[*r as usize]));
             let mid = clone[clone.len() / 2] as i32;
@@ -89,10 +94,10 @@ fn part1((constraints, token_lists):  (Vec<Constraint>, Vec<Vec<u8>>)) -> String
         }
     }
 
-    format!("{sum_1} / {sum_2}")
+    (sum_1, sum_2)
 }
 
-parsed_day!(
+combined_day!(
     nom_parsed_bytes(separated_pair(many1(parse_constraints), line_ending, many1(parse_pagelist))),
-    part1
+    solve
 );
\ No newline at end of file
@@ -171,4 +171,4 @@ fn part2(mut day: Day6) -> usize {
 }
 
 
-parsed_day!(parse, part1, part2);
\ No newline at end of file
+chained_day!(parse, part1, part2);
\ No newline at end of file
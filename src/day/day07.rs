@@ -5,7 +5,7 @@ use nom::IResult;
 use nom::multi::separated_list1;
 use nom::sequence::separated_pair;
 use crate::*;
-use crate::day::nom_parsed_bytes;
+use crate::day::{nom_parsed_bytes, Part};
 use crate::parse_helpers::parse_unsigned_nr_bytes;
 
 #[derive(Debug)]
@@ -46,7 +46,7 @@ fn parse(input: &[u8]) -> IResult<&[u8], Vec<Problem>> {
     separated_list1(line_ending, parse_problem)(input)
 }
 
-fn solve(input: Vec<Problem>) -> String {
+fn solve(input: Vec<Problem>, part: Part) -> (u64, u64) {
     let mut sum_1 = 0;
     let mut sum_2 = 0;
 
@@ -54,12 +54,12 @@ fn solve(input: Vec<Problem>) -> String {
         if can_solve(problem.target_value, problem.operands[0], &problem.operands[1..], false) {
             sum_1 += problem.target_value;
             sum_2 += problem.target_value;
-        } else if can_solve(problem.target_value, problem.operands[0], &problem.operands[1..], true) {
+        } else if part.includes_part2() && can_solve(problem.target_value, problem.operands[0], &problem.operands[1..], true) {
             sum_2 += problem.target_value;
         }
     }
 
-    format!("{} - {}", sum_1, sum_2)
+    (sum_1, sum_2)
 }
 
-parsed_day!(nom_parsed_bytes(parse), solve);
\ No newline at end of file
+combined_day!(nom_parsed_bytes(parse), solve);
\ No newline at end of file
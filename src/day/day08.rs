@@ -1,7 +1,7 @@
 use fxhash::FxHashSet;
 use crate::*;
 use crate::collections::{ArrayBag, Index2D, IndexMap, Location2D};
-use crate::day::parse_graphical_input;
+use crate::day::{parse_graphical_input, Part};
 
 #[derive(Debug, Default)]
 struct Day8 {
@@ -17,7 +17,7 @@ fn parse(input: &[u8]) -> Result<Day8, !> {
     Ok(result)
 }
 
-fn part1(input: Day8) -> String {
+fn solve(input: Day8, part: Part) -> (usize, usize) {
     let plausible = |location: Index2D| location.plausible() &&
         location.row <= input.terminus.row &&
         location.column <= input.terminus.column;
@@ -33,13 +33,13 @@ fn part1(input: Day8) -> String {
                     let single_step = *antenna1 + distance;
                     let index: Index2D = single_step.into();
 
-                    if plausible(index.into()){
+                    if part.includes_part1() && plausible(index.into()){
                         single_distance_outputs.insert(index);
                     }
 
                     let mut cursor = *antenna1;
 
-                    while plausible(cursor.into()) {
+                    while part.includes_part2() && plausible(cursor.into()) {
                         any_distance_outputs.insert(cursor);
                         cursor += distance
                     }
@@ -48,7 +48,7 @@ fn part1(input: Day8) -> String {
         }
     }
 
-    format!("{} - {}", single_distance_outputs.len(), any_distance_outputs.len())
+    (single_distance_outputs.len(), any_distance_outputs.len())
 }
 
-parsed_day!(parse, part1);
\ No newline at end of file
+combined_day!(parse, solve);
\ No newline at end of file
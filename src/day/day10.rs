@@ -2,8 +2,7 @@ use std::mem::swap;
 use fxhash::FxHashSet;
 use crate::*;
 use crate::collections::{CompassDirection, Index2D, Vec2D};
-use crate::day::parse_graphical_input;
-use crate::parse_helpers::infallible_parse;
+use crate::day::{parse_grid, GridError, Part};
 
 #[derive(Debug)]
 struct Tile {
@@ -11,37 +10,44 @@ struct Tile {
     tops_reachable: FxHashSet<Index2D>,
     rating: usize
 }
-fn parse(input: &[u8]) -> Vec2D<Tile> {
-    let mut result = Vec::new();
+fn parse(input: &[u8]) -> Result<Vec2D<Tile>, GridError> {
+    let mut map = parse_grid(input, b"", |byte| byte.is_ascii_digit().then(|| Tile {
+        elevation: (byte - b'0') as u32,
+        tops_reachable: FxHashSet::default(),
+        rating: 0,
+    }))?.grid;
 
-    let Index2D { column: columns, .. } = parse_graphical_input(input, |byte, here| {
-        let elevation = (byte - b'0') as u32;
-        let mut tops_reachable = FxHashSet::default();
-        let mut rating = 0;
-
-        if elevation == 9 {
-            tops_reachable.insert(here);
-            rating = 1;
+    for here in map.indices() {
+        let tile = &mut map[here];
+        if tile.elevation == 9 {
+            tile.tops_reachable.insert(here);
+            tile.rating = 1;
         }
+    }
 
-        result.push(Tile { elevation, tops_reachable, rating })
-    });
-
-    Vec2D::new_from_flat(result, columns)
+    Ok(map)
 }
 
-fn solve_both(mut input: Vec2D<Tile>) -> String {
+/**
+Collects the reachable tops and ratings layer by layer, from the tops down to the trailheads. The
+searches in `grid_search` only know passable tiles, not steps that must climb by exactly one, and
+visiting every tile once per layer is already cheaper than a search from each trailhead.
+*/
+fn solve_both(mut input: Vec2D<Tile>, part: Part) -> (usize, usize) {
     for height in (0..9).rev() {
         for idx in input.indices() {
             if input[idx].elevation == height {
                 for cd in CompassDirection::ALL {
                     let i2 = idx + cd;
                     if input.validate_index(i2) && input[i2].elevation == height + 1 {
+                        input[idx].rating += input[i2].rating;
+                        if !part.includes_part1() {
+                            continue;
+                        }
                         let mut tmp = FxHashSet::default();
                         // swap-and-back trick to prove aliasing safety
                         swap(&mut input[i2].tops_reachable, &mut tmp);
                         input[idx].tops_reachable.extend(&tmp);
-                        input[idx].rating += input[i2].rating;
                         swap(&mut input[i2].tops_reachable, &mut tmp);
                     }
                 }
@@ -59,7 +65,7 @@ fn solve_both(mut input: Vec2D<Tile>) -> String {
         }
     }
 
-    format!("{sum_ends} - {sum_ratings}")
+    (sum_ends, sum_ratings)
 }
 
-parsed_day!(infallible_parse(parse), solve_both);
\ No newline at end of file
+combined_day!(parse, solve_both);
\ No newline at end of file
@@ -6,8 +6,14 @@ use nom::IResult;
 use nom::multi::separated_list1;
 use crate::*;
 use crate::day::nom_parsed_bytes;
+use crate::parameters::{parameter, ParameterSpec};
 use crate::parse_helpers::parse_unsigned_nr_bytes;
 
+pub const PARAMETERS: &[ParameterSpec] = &[
+    ParameterSpec::new::<usize>("part1_blinks"),
+    ParameterSpec::new::<usize>("part2_blinks"),
+];
+
 #[derive(Debug)]
 struct Day11 {
     frequencies: FxHashMap<u64, usize>
@@ -46,11 +52,20 @@ fn parse(input: &[u8]) -> IResult<&[u8], Day11> {
     )(input)
 }
 
-parsed_day!(nom_parsed_bytes(parse), | v|{
-    for _ in 0..25 { v.apply_blink() }
+fn part1_blinks() -> usize {
+    parameter("part1_blinks", 25, 6)
+}
+
+fn part2_blinks() -> usize {
+    parameter("part2_blinks", 75, 25)
+}
+
+chained_day!(nom_parsed_bytes(parse), | v|{
+    for _ in 0..part1_blinks() { v.apply_blink() }
     v.frequencies.values().sum::<usize>()
 }, |mut v| {
-    for _ in 0..50 { v.apply_blink() }
+    // part 1 already blinked the stones
+    for _ in part1_blinks()..part2_blinks() { v.apply_blink() }
 
     v.frequencies.values().sum::<usize>()
 });
\ No newline at end of file
@@ -1,68 +1,54 @@
-use crate::collections::{CompassDirection, Slice2DVisor, Vec2D};
-use crate::{parsed_day, simple_day};
+use crate::collections::grid_search::connected_components;
+use crate::collections::{CompassDirection, Grid2D, Index2D, OrdinalDirection, Slice2DVisor, Vec2D};
+use crate::day::Part;
+use crate::{combined_day, reporting_day, simple_day};
 
-parsed_day!(|x|Ok::<_, !>(Slice2DVisor::new(x)), solve);
-fn solve(visor: Slice2DVisor) -> String {
-    let mut queue = Vec::new();
+combined_day!(|x|Ok::<_, !>(parse(x)), solve);
 
-    let mut assigned = Vec2D::new_from_flat(
-        vec![false; visor.rows() * visor.columns()],
-        visor.columns(),
-    );
-    let mut sum_1 = 0;
-    let mut sum_2 = 0;
-    for idx in assigned.indices() {
-        if assigned[idx] {
-            continue;
-        }
-
-        let mut area_size = 0;
-        let mut area_perimeter = 0;
-        let mut area_corner_count = 0;
-        let here = visor[idx];
-        queue.push(idx);
-
-        while let Some(next) = queue.pop() {
-            let mut detect_corner = |d1, d2| {
-                // outer corner pattern
-                // ..
-                // #.
-                if visor[next + d1] != here && visor[next + d2] != here {
-                    area_corner_count += 1;
-                }
-
-                // inner corner pattern
-                // #.
-                // ##
-                if visor[next + d1] == here && visor[next + d2] == here && visor[next + d1 + d2] != here {
-                    area_corner_count += 1;
-                }
-            };
-
-            if assigned[next] {
-                continue;
-            }
-            assigned[next] = true;
-
-            detect_corner(CompassDirection::NORTH, CompassDirection::WEST);
-            detect_corner(CompassDirection::NORTH, CompassDirection::EAST);
-            detect_corner(CompassDirection::SOUTH, CompassDirection::WEST);
-            detect_corner(CompassDirection::SOUTH, CompassDirection::EAST);
+fn parse(input: &[u8]) -> Vec2D<u8> {
+    let columns = Slice2DVisor::new(input).columns();
+    let plants = input.iter().copied().filter(|byte| *byte != b'\n').collect();
+    Vec2D::new_from_flat(plants, columns)
+}
 
-            area_size += 1;
-            for d in CompassDirection::ALL {
-                let neighbour = next + d;
-                if visor[neighbour] == here {
-                    queue.push(neighbour);
-                } else {
-                    area_perimeter += 1;
-                }
-            }
+fn solve(garden: Vec2D<u8>, part: Part) -> (usize, usize) {
+    let (regions, region_count) = connected_components(&garden, |a, b| a == b);
+    let mut area_size = vec![0; region_count];
+    let mut area_perimeter = vec![0; region_count];
+    let mut area_corner_count = vec![0; region_count];
+
+    for idx in regions.indices() {
+        let here = regions[idx];
+        let same_region = |neighbour: Index2D| regions.get(neighbour) == Some(&here);
+        let [north, east, south, west] = CompassDirection::ALL.map(|d| same_region(idx + d));
+        let is_corner = |side_1: bool, side_2: bool, diagonal: Index2D| {
+            // outer corner pattern
+            // ..
+            // #.
+            let outer = !side_1 && !side_2;
+            // inner corner pattern
+            // #.
+            // ##
+            let inner = side_1 && side_2 && !same_region(diagonal);
+            outer || inner
+        };
+
+        area_size[here] += 1;
+        if part.includes_part1() {
+            area_perimeter[here] += [north, east, south, west].into_iter().filter(|same| !same).count();
+        }
+        if part.includes_part2() {
+            area_corner_count[here] += [
+                is_corner(north, west, idx + OrdinalDirection::NORTHWEST),
+                is_corner(north, east, idx + OrdinalDirection::NORTHEAST),
+                is_corner(south, west, idx + OrdinalDirection::SOUTHWEST),
+                is_corner(south, east, idx + OrdinalDirection::SOUTHEAST),
+            ].into_iter().filter(|corner| *corner).count();
         }
-
-        sum_1 += area_perimeter * area_size;
-        sum_2 += area_corner_count * area_size;
     }
 
-    format!("{sum_1} - {sum_2}")
+    let sum_1 = (0..region_count).map(|region| area_perimeter[region] * area_size[region]).sum();
+    let sum_2 = (0..region_count).map(|region| area_corner_count[region] * area_size[region]).sum();
+
+    (sum_1, sum_2)
 }
@@ -21,42 +21,97 @@ Integer solutions only
 
 #[derive(Debug)]
 struct Linear2x2System {
-    a: [[f64; 2]; 2],
-    b: [f64; 2],
+    a: [[i128; 2]; 2],
+    b: [i128; 2],
+}
+
+const TOKENS_A: i128 = 3;
+const TOKENS_B: i128 = 1;
+const PRIZE_OFFSET: i128 = 10000000000000;
+
+/** Returns `(g, s, t)` with `g = gcd(a, b) = s * a + t * b` */
+fn extended_gcd(a: i128, b: i128) -> (i128, i128, i128) {
+    if b == 0 {
+        (a, 1, 0)
+    } else {
+        let (g, s, t) = extended_gcd(b, a % b);
+        (g, t, s - (a / b) * t)
+    }
+}
+
+/**
+Cheapest non-negative solution of `x * p + y * q = n`, for `p` and `q` not both zero. All solutions
+are `x = x0 + k * q / g`, `y = y0 - k * p / g`, and as the token cost is linear in `k`, the
+cheapest one sits at one end of the range of `k` keeping both non-negative.
+*/
+fn cheapest_on_line(p: i128, q: i128, n: i128) -> Option<(i128, i128)> {
+    let (g, s, t) = extended_gcd(p, q);
+    if n % g != 0 {
+        return None;
+    }
+    let (x0, y0) = (s * (n / g), t * (n / g));
+    let (dx, dy) = (q / g, p / g);
+
+    let lowest = if dx > 0 { Some(-(x0.div_euclid(dx))) } else if x0 >= 0 { None } else { return None };
+    let highest = if dy > 0 { Some(y0.div_euclid(dy)) } else if y0 >= 0 { None } else { return None };
+    if let (Some(lowest), Some(highest)) = (lowest, highest) {
+        if lowest > highest {
+            return None;
+        }
+    }
+
+    let k = if TOKENS_A * dx - TOKENS_B * dy >= 0 { lowest.or(highest) } else { highest.or(lowest) }?;
+    Some((x0 + k * dx, y0 - k * dy))
 }
 
 fn solve_2x2_linear_system(linear_2x2: &Linear2x2System) -> Option<(u64, u64)> {
     let Linear2x2System { a, b } = linear_2x2;
     let det = a[0][0] * a[1][1] - a[0][1] * a[1][0];
-    if det.abs() < f64::EPSILON {
-        return None; // Degenerate matrix, no unique solution
-    }
 
-    let x = (b[0] * a[1][1] - b[1] * a[0][1]) / det;
-    let y = (a[0][0] * b[1] - a[1][0] * b[0]) / det;
+    let (x, y) = if det != 0 {
+        let x = b[0] * a[1][1] - b[1] * a[0][1];
+        let y = a[0][0] * b[1] - a[1][0] * b[0];
+        if x % det != 0 || y % det != 0 {
+            return None;
+        }
+        (x / det, y / det)
+    } else {
+        // both buttons move along the same line - the prize needs to be on it as well
+        let on_line = |column: usize| a[0][column] * b[1] - a[1][column] * b[0] == 0;
+        if !on_line(0) || !on_line(1) {
+            return None;
+        }
+
+        if a[0] != [0, 0] {
+            cheapest_on_line(a[0][0], a[0][1], b[0])?
+        } else if a[1] != [0, 0] {
+            cheapest_on_line(a[1][0], a[1][1], b[1])?
+        } else if *b == [0, 0] {
+            (0, 0)
+        } else {
+            return None;
+        }
+    };
 
-    if x != x.floor()
-        || y != y.floor()
-        || x < 0.0
-        || y < 0.0
-    {
+    if x < 0 || y < 0 {
         None
     } else {
         Some((x as u64, y as u64))
     }
 }
 
-streaming_day!(parse, handle, |(a, b)|format!("{a} - {b}"));
+streaming_day!(parse, handle);
 
 fn handle(state: &mut (u64, u64), mut next: Linear2x2System) {
-    if let Some((x, y)) = solve_2x2_linear_system(&next) {
-        state.0 += 3 * x + y
+    let tokens = |(x, y): (u64, u64)| TOKENS_A as u64 * x + TOKENS_B as u64 * y;
+    if let Some(presses) = solve_2x2_linear_system(&next) {
+        state.0 += tokens(presses)
     }
-    next.b[0] += 10000000000000.;
-    next.b[1] += 10000000000000.;
+    next.b[0] += PRIZE_OFFSET;
+    next.b[1] += PRIZE_OFFSET;
 
-    if let Some((x, y)) = solve_2x2_linear_system(&next) {
-        state.1 += 3 * x + y
+    if let Some(presses) = solve_2x2_linear_system(&next) {
+        state.1 += tokens(presses)
     }
 }
 
@@ -85,3 +140,35 @@ fn parse(input: &[u8]) -> IResult<&[u8], Linear2x2System> {
         },
     )(input)
 }
+
+#[cfg(test)]
+mod test {
+    use crate::day::day13::{solve_2x2_linear_system, Linear2x2System};
+
+    fn presses(button_a: (i128, i128), button_b: (i128, i128), prize: (i128, i128)) -> Option<(u64, u64)> {
+        solve_2x2_linear_system(&Linear2x2System {
+            a: [[button_a.0, button_b.0], [button_a.1, button_b.1]],
+            b: [prize.0, prize.1],
+        })
+    }
+
+    #[test]
+    fn collinear_prize_on_line() {
+        assert_eq!(Some((2, 1)), presses((2, 2), (3, 3), (7, 7)));
+    }
+
+    #[test]
+    fn collinear_prize_off_line() {
+        assert_eq!(None, presses((1, 1), (2, 2), (4, 5)));
+    }
+
+    #[test]
+    fn collinear_a_cheaper() {
+        assert_eq!(Some((2, 0)), presses((4, 4), (1, 1), (8, 8)));
+    }
+
+    #[test]
+    fn collinear_b_cheaper() {
+        assert_eq!(Some((0, 2)), presses((1, 1), (2, 2), (4, 4)));
+    }
+}
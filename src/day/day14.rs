@@ -1,4 +1,8 @@
-use std::fmt::{Display, Formatter, Write};
+use std::fs::{create_dir_all, write};
+use std::io;
+use std::ops::RangeInclusive;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
 use crate::collections::{Index2D, Vec2D};
 use crate::parse_helpers::parse_signed_nr_bytes;
 use crate::*;
@@ -8,7 +12,18 @@ use nom::combinator::map;
 use nom::sequence::{preceded, separated_pair};
 use nom::IResult;
 use nom::multi::{separated_list0};
-use crate::day::{nom_parsed_bytes, visual_inspection};
+use crate::day::nom_parsed_bytes;
+use crate::parameters::{optional_parameter, parameter, ParameterSpec};
+use crate::ui::UIWrite;
+
+pub const PARAMETERS: &[ParameterSpec] = &[
+    ParameterSpec::new::<usize>("height"),
+    ParameterSpec::new::<usize>("width"),
+    ParameterSpec::new::<PathBuf>("frames_dir"),
+    ParameterSpec::new::<usize>("frames_from"),
+    ParameterSpec::new::<usize>("frames_to"),
+    ParameterSpec::new::<FrameFormat>("frames_format"),
+];
 
 #[derive(Debug, Clone)]
 struct Robot {
@@ -16,30 +31,63 @@ struct Robot {
     velocity: Index2D,
 }
 
-const HEIGHT: usize = 103;
-const WIDTH: usize = 101;
+#[derive(Debug, Clone, Copy)]
+struct Area {
+    height: usize,
+    width: usize,
+}
+
+impl Area {
+    fn from_parameters() -> Self {
+        Self {
+            height: parameter("height", 103, 7),
+            width: parameter("width", 101, 11),
+        }
+    }
+
+    fn wrap(&self, (x, y): (i64, i64)) -> Index2D {
+        Index2D {
+            row: y.rem_euclid(self.height as i64) as usize,
+            column: x.rem_euclid(self.width as i64) as usize,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Bathroom {
+    area: Area,
+    robots: Vec<Robot>,
+}
 
 impl Robot {
-    fn step(&mut self) {
+    fn after(&self, steps: usize, area: Area) -> Robot {
+        let position = Index2D {
+            row: (self.position.row + self.velocity.row * (steps % area.height)) % area.height,
+            column: (self.position.column + self.velocity.column * (steps % area.width)) % area.width,
+        };
+        Robot { position, velocity: self.velocity }
+    }
+
+    fn step(&mut self, area: Area) {
         self.position.row += self.velocity.row;
         self.position.column += self.velocity.column;
-        self.position.row %= HEIGHT;
-        self.position.column %= WIDTH;
+        self.position.row %= area.height;
+        self.position.column %= area.width;
     }
 
-    fn assign_to_quadrant(&self, quadrants: &mut [usize]) {
-        if self.position.row == (HEIGHT / 2) || self.position.column == (WIDTH / 2) {
+    fn assign_to_quadrant(&self, area: Area, quadrants: &mut [usize]) {
+        if self.position.row == (area.height / 2) || self.position.column == (area.width / 2) {
             return;
         }
 
-        if self.position.row < HEIGHT / 2 {
-            if self.position.column < WIDTH / 2 {
+        if self.position.row < area.height / 2 {
+            if self.position.column < area.width / 2 {
                 quadrants[0] += 1;
             } else {
                 quadrants[1] += 1;
             }
         } else {
-            if self.position.column < WIDTH / 2 {
+            if self.position.column < area.width / 2 {
                 quadrants[2] += 1;
             } else {
                 quadrants[3] += 1;
@@ -48,85 +96,163 @@ impl Robot {
     }
 }
 
-fn parse_location(input: &[u8]) -> IResult<&[u8], Index2D> {
-    map(
-        separated_pair(parse_signed_nr_bytes::<i64>, tag(b","), parse_signed_nr_bytes::<i64>),
-        |(mut x, mut y)| {
-            if x < 0 {
-                x += WIDTH as i64;
-            };
-            if y < 0 {
-                y += HEIGHT as i64;
-            }
-            Index2D { row: y as usize, column: x as usize }
-        },
-    )(input)
+fn parse_location(input: &[u8]) -> IResult<&[u8], (i64, i64)> {
+    separated_pair(parse_signed_nr_bytes::<i64>, tag(b","), parse_signed_nr_bytes::<i64>)(input)
 }
-fn parse_line(input: &[u8]) -> IResult<&[u8], Robot> {
-    map(preceded(
+
+fn parse_line(area: Area) -> impl FnMut(&[u8]) -> IResult<&[u8], Robot> {
+    move |input| map(preceded(
         tag(b"p="),
         separated_pair(parse_location, tag(b" v="), parse_location),
-    ), |(position, velocity)| Robot { position, velocity })(input)
+    ), |(position, velocity)| Robot { position: area.wrap(position), velocity: area.wrap(velocity) })(input)
+}
+
+fn parse(input: &[u8]) -> IResult<&[u8], Bathroom> {
+    let area = Area::from_parameters();
+    map(separated_list0(line_ending, parse_line(area)), move |robots| Bathroom { area, robots })(input)
 }
 
-fn p1(robots: &mut Vec<Robot>) -> usize {
-    let mut robots = robots.clone();
+fn p1(bathroom: &mut Bathroom) -> usize {
+    let area = bathroom.area;
+    let mut robots = bathroom.robots.clone();
     for _ in 0..100 {
         for robot in robots.iter_mut() {
-            robot.step();
+            robot.step(area);
         }
     }
 
     let mut quadrants = [0usize; 4];
     for robot in robots {
-        robot.assign_to_quadrant(&mut quadrants);
+        robot.assign_to_quadrant(area, &mut quadrants);
     }
 
     quadrants.iter().product()
 }
 
-struct DisplayType<'a>(&'a [Robot]);
-
-impl Display for DisplayType<'_> {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        for row in 0..HEIGHT {
-            for column in 0..WIDTH {
-                let char =
-                    if self.0.iter().any(|robot| robot.position == Index2D{ row, column }) {
-                        '#'
-                    } else {
-                        '.'
-                    };
-                f.write_char(char)?;
-            }
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum FrameFormat {
+    Text,
+    /// plain portable bitmap, viewable by most image viewers
+    Pbm,
+    /// plain portable graymap, brighter where more robots share a tile
+    Pgm,
+}
 
-            f.write_char('\n')?;
+impl FrameFormat {
+    fn extension(self) -> &'static str {
+        match self {
+            FrameFormat::Text => "txt",
+            FrameFormat::Pbm => "pbm",
+            FrameFormat::Pgm => "pgm",
         }
-
-        Ok(())
     }
 }
-fn p2(mut robots: Vec<Robot>) -> usize {
-    let mut i = 0;
-    loop {
-        let mut counts = Vec2D::new_from_flat(vec![0i32; (HEIGHT * WIDTH) as usize], WIDTH as usize);
 
-        i += 1;
-        for robot in robots.iter_mut() {
-            robot.step();
-            counts[robot.position]+=1;
+impl FromStr for FrameFormat {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "txt" | "text" => Ok(FrameFormat::Text),
+            "pbm" => Ok(FrameFormat::Pbm),
+            "pgm" => Ok(FrameFormat::Pgm),
+            _ => Err(()),
         }
-        let mut quadrants = [0usize; 4];
-        for robot in robots.iter() {
-            robot.assign_to_quadrant(&mut quadrants);
+    }
+}
+
+impl FrameFormat {
+    fn frame(self, robots: &[Robot], area: Area) -> String {
+        let mut robots_at = Vec2D::filled(0usize, area.height, area.width);
+        for robot in robots {
+            robots_at[robot.position] += 1;
         }
 
-        if !counts.as_slice().iter().any(|x| *x > 1) {
-            if visual_inspection(DisplayType(&robots), i) {
-                return i
-            }
+        let (header, robot_char, empty_char) = match self {
+            FrameFormat::Text => (String::new(), '#', '.'),
+            FrameFormat::Pbm => (format!("P1\n{} {}\n", area.width, area.height), '1', '0'),
+            FrameFormat::Pgm => return Self::graymap(&robots_at, area),
+        };
+        let picture = robots_at.render(|count| if *count > 0 { robot_char } else { empty_char });
+        format!("{header}{picture}\n")
+    }
+
+    fn graymap(robots_at: &Vec2D<usize>, area: Area) -> String {
+        let brightest = robots_at.as_slice().iter().copied().max().unwrap_or_default().max(1);
+        let mut graymap = format!("P2\n{} {}\n{brightest}\n", area.width, area.height);
+        for row in 0..area.height {
+            let shades: Vec<_> = robots_at[row].iter().map(|count| count.to_string()).collect();
+            graymap.push_str(&shades.join(" "));
+            graymap.push('\n');
         }
+        graymap
+    }
+}
+
+/** Steps exported before and after the tree unless a step range is given */
+const FRAMES_AROUND_TREE: usize = 5;
+
+fn export_frames(directory: &Path, steps: RangeInclusive<usize>, format: FrameFormat, robots: &[Robot], area: Area) -> io::Result<()> {
+    create_dir_all(directory)?;
+    for step in steps {
+        let moved: Vec<_> = robots.iter().map(|robot| robot.after(step, area)).collect();
+        let path = directory.join(format!("step{step:05}.{}", format.extension()));
+        write(path, format.frame(&moved, area))?;
     }
+
+    Ok(())
+}
+
+/** Spread of the coordinates, scaled by the square of their count to stay in integers */
+fn spread(coordinates: impl Iterator<Item = usize>) -> u64 {
+    let (count, sum, sum_of_squares) = coordinates.fold((0u64, 0u64, 0u64), |(count, sum, squares), coordinate| {
+        let coordinate = coordinate as u64;
+        (count + 1, sum + coordinate, squares + coordinate * coordinate)
+    });
+    count * sum_of_squares - sum * sum
+}
+
+/** Step (modulo the period) at which the coordinates of all robots are clustered most tightly */
+fn tightest_step(period: usize, robots: &[Robot], coordinate: impl Fn(&Robot, usize) -> usize) -> usize {
+    (0..period)
+        .min_by_key(|step| spread(robots.iter().map(|robot| coordinate(robot, *step))))
+        .unwrap()
+}
+
+/**
+Rows repeat every `height` steps and columns every `width` steps. The tree clusters the robots
+in both dimensions, so the steps with the least spread of the columns and rows are its step
+modulo `width` and `height`, and the coprime periods give its step by the Chinese remainder theorem.
+*/
+fn p2(&Bathroom { area, ref robots }: &Bathroom) -> usize {
+    let column_step = tightest_step(area.width, robots, |robot, step| robot.after(step, area).position.column);
+    let row_step = tightest_step(area.height, robots, |robot, step| robot.after(step, area).position.row);
+
+    (0..area.height)
+        .map(|n| column_step + n * area.width)
+        .find(|step| step % area.height == row_step)
+        .expect("Width and height are coprime")
+}
+
+/**
+Exports the frames if a directory is configured. Without an explicit step range, the frames
+around the tree step are exported, searching for the tree only if part 2 did not run.
+*/
+fn export_requested_frames(bathroom: &Bathroom, tree_step: Option<&usize>, out: &mut impl UIWrite) -> Result<(), String> {
+    let Some(directory) = optional_parameter::<PathBuf>("frames_dir") else {
+        return Ok(());
+    };
+
+    let mut tree_step = tree_step.copied();
+    let mut tree_step = || *tree_step.get_or_insert_with(|| p2(bathroom));
+    let first = optional_parameter("frames_from").unwrap_or_else(|| tree_step().saturating_sub(FRAMES_AROUND_TREE));
+    let last = optional_parameter("frames_to").unwrap_or_else(|| tree_step() + FRAMES_AROUND_TREE);
+    let format = optional_parameter("frames_format").unwrap_or(FrameFormat::Pbm);
+
+    export_frames(&directory, first..=last, format, &bathroom.robots, bathroom.area)
+        .map_err(|e| format!("Cannot export frames to {}: {e}", directory.display()))?;
+    out.info(format_args!("Exported steps {first} to {last} to {}", directory.display()));
+    Ok(())
 }
 
-parsed_day!(nom_parsed_bytes(separated_list0(line_ending, parse_line)), p1, p2);
+exporting_day!(nom_parsed_bytes(parse), p1, p2, export_requested_frames);
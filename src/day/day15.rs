@@ -1,5 +1,6 @@
-use crate::collections::{CompassDirection, Index2D};
+use crate::collections::{CompassDirection, Index2D, Vec2D};
 use crate::day::{nom_parsed_bytes, parse_graphical_input};
+use crate::ui::UIWrite;
 use crate::*;
 use nom::bytes::complete::take_until;
 use nom::{AsBytes, IResult};
@@ -254,47 +255,45 @@ fn parse(input: &[u8]) -> IResult<&[u8], Day15> {
     ))
 }
 
-fn solve1(day: &mut Day15) -> usize {
+/** Shows the warehouse, drawing each box over as many tiles as `box_chars` has chars */
+fn show_warehouse(day: &Day15, box_chars: &str, out: &mut impl UIWrite, caption: &str) {
+    if !out.shows_info() {
+        return;
+    }
+
+    let rows = day.walls.iter().fold(day.player.row + 1, |rows, wall| rows.max(wall.row + 1));
+    let columns = day.walls.iter().fold(day.player.column + 1, |columns, wall| columns.max(wall.column + 1));
+    let mut walls = Vec2D::filled(false, rows, columns);
+    for wall in &day.walls {
+        walls[*wall] = true;
+    }
+
+    let mut rendering = walls.render(|wall| if *wall { '#' } else { '.' });
+    for (offset, box_char) in box_chars.chars().enumerate() {
+        let box_parts = day.boulders.iter().map(|idx| Index2D { row: idx.row, column: idx.column + offset });
+        rendering = rendering.mark(box_parts, box_char);
+    }
+    rendering.mark([day.player], '@').write_to(out, caption);
+}
+
+fn solve1(day: &mut Day15, out: &mut impl UIWrite) -> usize {
     let mut day = day.clone();
     for movement in day.moves.clone().iter() {
         day.apply_move(*movement);
     }
 
+    show_warehouse(&day, "O", out, "Warehouse after all moves");
     day.gps_score()
 }
 
-fn dbg_print(day: &Day15) {
-    let mut buffer = String::new();
-    let rows = 0..=day.walls.iter().map(|i| i.row).max().unwrap();
-    let cols = 0..=day.walls.iter().map(|i| i.column).max().unwrap();
-    for row in rows {
-        for column in cols.clone() {
-            let idx = Index2D { row, column};
-            let char = if day.walls.contains(&idx) {
-                '#'
-            } else if day.player == idx {
-                '@'
-            } else if day.boulders.contains(&idx) {
-                '['
-            } else if day.boulders.contains(&(idx + CompassDirection::WEST)){
-                ']'
-            } else {
-                '.'
-            };
-            buffer.push(char);
-        }
-        buffer.push('\n');
-    }
-    println!("{}", buffer);
-}
-
-fn solve2(mut day: Day15) -> usize {
+fn solve2(mut day: Day15, out: &mut impl UIWrite) -> usize {
     day.expand();
     for movement in day.moves.clone().iter() {
         day.apply_move_2(*movement);
     }
 
+    show_warehouse(&day, "[]", out, "Wide warehouse after all moves");
     day.gps_score()
 }
 
-parsed_day!(nom_parsed_bytes(parse), solve1, solve2);
+reporting_day!(nom_parsed_bytes(parse), solve1, solve2);
@@ -1,7 +1,8 @@
-use crate::collections::{ArrayBag, CompassDirection, Index2D, Vec2D};
-use crate::day::parse_graphical_input;
+use crate::collections::grid_search::{dijkstra, UNREACHED};
+use crate::collections::{ArrayBag, CompassDirection, Grid2D, Index2D, Vec2D};
+use crate::day::{parse_grid, OptionalAnswer, Part, SimpleError};
+use crate::ui::UIWrite;
 use crate::*;
-use std::collections::HashSet;
 
 #[derive(Debug, Eq, PartialEq, Clone, Hash)]
 struct Node {
@@ -16,20 +17,15 @@ struct Day17 {
     blocker_map: Vec2D<bool>,
 }
 
-fn parse(input: &[u8]) -> Result<Day17, !> {
-    let mut temp = Vec::new();
-    let mut start = Index2D::IMPLAUSIBLE;
-    let mut end = Index2D::IMPLAUSIBLE;
-    let Index2D { column, row } = parse_graphical_input(input, |next, idx| match next {
-        b'S' => start = idx,
-        b'#' => temp.push(idx),
-        b'E' => end = idx,
-        _ => (),
-    });
-    let mut blocker_map = Vec2D::filled(false, column + 1, row + 1);
-    for idx in temp {
-        blocker_map[idx] = true;
-    }
+fn parse(input: &[u8]) -> Result<Day17, SimpleError> {
+    let parsed = parse_grid(input, b"SE", |byte| match byte {
+        b'#' => Some(true),
+        b'.' | b'S' | b'E' => Some(false),
+        _ => None,
+    })?;
+    let start = parsed.marker(b'S')?;
+    let end = parsed.marker(b'E')?;
+    let blocker_map = parsed.grid;
 
     Ok(Day17 {
         start: Node {
@@ -41,49 +37,171 @@ fn parse(input: &[u8]) -> Result<Day17, !> {
     })
 }
 
-fn p1(day: Day17) -> String {
-    let (iter, score) = pathfinding::directed::astar::astar_bag(
-        &day.start,
-        |node| {
-            let mut buffer = ArrayBag::<_, 3>::default();
-            buffer.insert((
-                Node {
-                    position: node.position,
-                    facing: node.facing.turn_right(),
-                },
-                1000,
-            ));
-            buffer.insert((
-                Node {
-                    position: node.position,
-                    facing: node.facing.turn_left(),
-                },
-                1000,
-            ));
-            let ahead = node.position + node.facing;
-            if !day.blocker_map[ahead] {
-                buffer.insert((
-                    Node {
-                        position: ahead,
-                        facing: node.facing,
-                    },
-                    1,
-                ));
-            }
-            buffer
-        },
-        |node| node.position.manhattan_distance(day.end),
-        |node| node.position == day.end,
-    ).unwrap();
-
-    let mut best = HashSet::new();
-    for path in iter {
-        for Node { position, .. } in path {
-            best.insert(position);
+const TURN_COST: usize = 1000;
+const STEP_COST: usize = 1;
+
+fn moves(day: &Day17, node: &Node) -> ArrayBag<(Node, usize), 3> {
+    let mut buffer = ArrayBag::default();
+    buffer.insert((Node { position: node.position, facing: node.facing.turn_right() }, TURN_COST));
+    buffer.insert((Node { position: node.position, facing: node.facing.turn_left() }, TURN_COST));
+    let ahead = node.position + node.facing;
+    if day.blocker_map.get(ahead) == Some(&false) {
+        buffer.insert((Node { position: ahead, facing: node.facing }, STEP_COST));
+    }
+    buffer
+}
+
+/** Moves leading into `node`, i.e. the moves of the reversed maze */
+fn reverse_moves(day: &Day17, node: &Node) -> ArrayBag<(Node, usize), 3> {
+    let mut buffer = ArrayBag::default();
+    buffer.insert((Node { position: node.position, facing: node.facing.turn_right() }, TURN_COST));
+    buffer.insert((Node { position: node.position, facing: node.facing.turn_left() }, TURN_COST));
+    let behind = node.position - node.facing;
+    if day.blocker_map.get(behind) == Some(&false) {
+        buffer.insert((Node { position: behind, facing: node.facing }, STEP_COST));
+    }
+    buffer
+}
+
+fn facing_index(facing: CompassDirection) -> usize {
+    CompassDirection::ALL.iter().position(|d| *d == facing).unwrap()
+}
+
+/** Lowest score reaching every position, per facing in the order of `CompassDirection::ALL` */
+fn scores_from<Moves: IntoIterator<Item = (Node, usize)>>(
+    day: &Day17,
+    sources: impl IntoIterator<Item = Node>,
+    moves: impl Fn(&Day17, &Node) -> Moves,
+) -> Vec2D<[usize; 4]> {
+    let sources = sources.into_iter().map(|source| (source.position, facing_index(source.facing)));
+    dijkstra(&day.blocker_map, sources, |position, facing| {
+        let node = Node { position, facing: CompassDirection::ALL[facing] };
+        moves(day, &node).into_iter().map(|(next, cost)| ((next.position, facing_index(next.facing)), cost))
+    })
+}
+
+/** Follows moves that keep the score on par with the best, stepping along one of the best paths */
+fn one_best_path(day: &Day17, from_start: &Vec2D<[usize; 4]>, to_end: &Vec2D<[usize; 4]>, best: usize) -> Vec<(Index2D, CompassDirection)> {
+    let mut node = day.start.clone();
+    let mut steps = Vec::new();
+    while node.position != day.end {
+        let score = from_start[node.position][facing_index(node.facing)];
+        let (next, _) = moves(day, &node).into_iter()
+            .find(|(next, cost)| {
+                let facing = facing_index(next.facing);
+                from_start[next.position][facing] == score + cost
+                    && best.checked_sub(score + cost) == Some(to_end[next.position][facing])
+            })
+            .unwrap();
+        if next.position != node.position {
+            steps.push((node.position, node.facing));
         }
+        node = next;
+    }
+    steps
+}
+
+/**
+Scores from the start and, through the reversed moves, to the end from every node. A tile is on a
+best path if, in some facing, both scores add up to the best score - no path needs to be enumerated.
+*/
+fn solve(day: Day17, part: Part, out: &mut impl UIWrite) -> (OptionalAnswer<usize>, OptionalAnswer<usize>) {
+    let from_start = scores_from(&day, [day.start.clone()], moves);
+    let best = from_start[day.end].into_iter().min().unwrap();
+    if best == UNREACHED {
+        out.critical(format_args!("No path leads from the start {:?} to the end {:?}", day.start.position, day.end));
+        return (OptionalAnswer(None), OptionalAnswer(None));
+    }
+    if !part.includes_part2() {
+        return (OptionalAnswer(Some(best)), OptionalAnswer(None));
+    }
+
+    let ends = CompassDirection::ALL.map(|facing| Node { position: day.end, facing });
+    let to_end = scores_from(&day, ends, reverse_moves);
+
+    let mut on_best_path = Vec2D::filled(false, day.blocker_map.rows(), day.blocker_map.row_length());
+    for position in day.blocker_map.indices() {
+        on_best_path[position] = from_start[position].iter().zip(to_end[position])
+            .any(|(from, to)| from.checked_add(to) == Some(best));
+    }
+    if out.shows_info() {
+        let best_tiles = on_best_path.indices().filter(|position| on_best_path[*position]);
+        day.blocker_map.render(|blocked| if *blocked { '#' } else { '.' })
+            .mark(best_tiles, 'O')
+            .path(one_best_path(&day, &from_start, &to_end, best))
+            .mark([day.start.position], 'S')
+            .mark([day.end], 'E')
+            .write_to(out, "Tiles on best paths, one of them drawn as arrows");
     }
 
-    format!("Part 1 (score): {score}, Part 2 (distinct best-path squares): {}", best.len())
+    (OptionalAnswer(Some(best)), OptionalAnswer(Some(on_best_path.as_slice().iter().filter(|tile| **tile).count())))
 }
 
-parsed_day!(parse, p1);
+reporting_day!(parse, solve);
+
+#[cfg(test)]
+mod test {
+    use crate::day::day16::{parse, solve};
+    use crate::day::Part;
+    use crate::ui::{Terse, UIFactory};
+
+    const FIRST_EXAMPLE: &[u8] = b"\
+###############
+#.......#....E#
+#.#.###.#.###.#
+#.....#.#...#.#
+#.###.#####.#.#
+#.#.#.......#.#
+#.#.#####.###.#
+#...........#.#
+###.#.#####.#.#
+#...#.....#.#.#
+#.#.#.###.#.#.#
+#.....#...#.#.#
+#.###.#.#.#.#.#
+#S..#.....#...#
+###############
+";
+
+    const SECOND_EXAMPLE: &[u8] = b"\
+#################
+#...#...#...#..E#
+#.#.#.#.#.#.#.#.#
+#.#.#.#...#...#.#
+#.#.#.#.###.#.#.#
+#...#.#.#.....#.#
+#.#.#.#.#.#####.#
+#.#...#.#.#.....#
+#.#.#####.#.###.#
+#.#.#.......#...#
+#.#.###.#####.###
+#.#.#...#.....#.#
+#.#.#.#####.###.#
+#.#.#.........#.#
+#.#.#.#########.#
+#S#.............#
+#################
+";
+
+    fn solved(input: &[u8], part: Part) -> (Option<usize>, Option<usize>) {
+        let mut sink = std::io::sink();
+        let (part1, part2) = solve(parse(input).unwrap(), part, &mut Terse::create(&mut sink, "test"));
+        (part1.0, part2.0)
+    }
+
+    #[test]
+    fn first_example() {
+        assert_eq!((Some(7036), Some(45)), solved(FIRST_EXAMPLE, Part::Both));
+    }
+
+    #[test]
+    fn second_example() {
+        assert_eq!((Some(11048), Some(64)), solved(SECOND_EXAMPLE, Part::Both));
+        assert_eq!((Some(11048), None), solved(SECOND_EXAMPLE, Part::One));
+    }
+
+    #[test]
+    fn unreachable_end_is_unsolved() {
+        assert_eq!((None, None), solved(b"#####\n#S#E#\n#####\n", Part::Both));
+    }
+}
@@ -1,5 +1,6 @@
-use crate::day::nom_parsed_bytes;
+use crate::day::{nom_parsed_bytes, OptionalAnswer};
 use crate::parse_helpers::parse_unsigned_nr_bytes;
+use crate::ui::UIWrite;
 use crate::*;
 use nom::branch::alt;
 use nom::bytes::complete::tag;
@@ -7,6 +8,7 @@ use nom::combinator::{map, value};
 use nom::multi::separated_list1;
 use nom::sequence::tuple;
 use nom::IResult;
+use std::fmt::{Display, Formatter};
 use std::rc::Rc;
 
 #[derive(Debug, Clone)]
@@ -19,8 +21,91 @@ struct VM {
     output: Vec<u8>,
 }
 
+const MNEMONICS: [&str; 8] = ["adv", "bxl", "bst", "jnz", "bxc", "out", "bdv", "cdv"];
+
+/** Instructions taking a literal operand - all others take a combo operand, except bxc ignoring it */
+const LITERAL_OPERAND: [bool; 8] = [false, true, false, true, false, false, false, false];
+
+struct Operand {
+    instruction: u8,
+    operand: u8,
+}
+
+impl Display for Operand {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.instruction == 4 {
+            return Ok(());
+        }
+        if LITERAL_OPERAND[self.instruction as usize] {
+            return f.write_fmt(format_args!(" {}", self.operand));
+        }
+        match self.operand {
+            0..=3 => f.write_fmt(format_args!(" {}", self.operand)),
+            4 => f.write_str(" A"),
+            5 => f.write_str(" B"),
+            6 => f.write_str(" C"),
+            _ => f.write_str(" <reserved>"),
+        }
+    }
+}
+
+/** Mnemonic form of a program, one instruction per line (each starting with a line break) */
+struct Disassembly<'a>(&'a [u8]);
+
+impl Display for Disassembly<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for (pair_index, pair) in self.0.chunks(2).enumerate() {
+            let &[instruction, operand] = pair else {
+                return f.write_fmt(format_args!("\n{:02}: <truncated>", pair_index * 2));
+            };
+            f.write_fmt(format_args!(
+                "\n{:02}: {}{}",
+                pair_index * 2,
+                MNEMONICS[instruction as usize],
+                Operand { instruction, operand }
+            ))?;
+        }
+
+        Ok(())
+    }
+}
+
+/** The next instruction of the VM, along with the register contents before executing it */
+struct Trace<'a>(&'a VM);
+
+impl Display for Trace<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let vm = self.0;
+        let instruction = vm.program[vm.instruction_pointer];
+        let operand = vm.program[vm.instruction_pointer + 1];
+        f.write_fmt(format_args!(
+            "{:02}: {:<8} A={} B={} C={}",
+            vm.instruction_pointer,
+            format!("{}{}", MNEMONICS[instruction as usize], Operand { instruction, operand }),
+            vm.register_a,
+            vm.register_b,
+            vm.register_c
+        ))
+    }
+}
+
 impl VM {
+    fn halted(&self) -> bool {
+        self.instruction_pointer >= self.program.len()
+    }
 
+    fn run(&mut self) {
+        while !self.halted() {
+            self.step();
+        }
+    }
+
+    fn output_for(&self, register_a: i64) -> Vec<u8> {
+        let mut vm = self.clone();
+        vm.register_a = register_a;
+        vm.run();
+        vm.output
+    }
 
     fn step(&mut self) {
         let instruction = self.program[self.instruction_pointer];
@@ -107,39 +192,70 @@ fn parse(input: &[u8]) -> IResult<&[u8], VM> {
     )(input)
 }
 
-fn solve(day: VM) -> String {
-    let program_length = day.program.len();
-    let mut day_part_1 = day.clone();
-    while day_part_1.instruction_pointer < day_part_1.program.len() {
-        day_part_1.step();
+/**
+Programs reproducing themselves shift A by 3 bits per output, with the last output depending on
+the highest bits only. Searching from the last output backwards, each further output fixes 3
+more bits of A - trying all 8 candidates in ascending order, backtracking when a candidate
+leads nowhere, finds the lowest value.
+*/
+fn find_quine_seed(vm: &VM, register_a: i64, matched: usize) -> Option<i64> {
+    let program = vm.program.as_slice();
+    if matched == program.len() {
+        return Some(register_a);
     }
 
-    let output_part_1 = day_part_1.output;
-
-    let mut i = 0usize;
-    let mut match_length = 1;
-    loop {
-        let mut day = day.clone();
-        day.register_a = i as i64;
-
-        while day.instruction_pointer < day.program.len() {
-            day.step();
-        }
-        let output = day.output.as_slice();
-        let program_suffix = &day.program[program_length - match_length..];
-        if output == program_suffix {
-            if match_length ==  day.program.len() {
-                break;
+    for candidate in 0..8 {
+        let next = (register_a << 3) | candidate;
+        if vm.output_for(next) == program[program.len() - matched - 1..] {
+            if let Some(seed) = find_quine_seed(vm, next, matched + 1) {
+                return Some(seed);
             }
-            match_length += 1;
-            i <<= 3;
-        } else {
-            i += 1;
         }
+    }
+
+    None
+}
+
+fn part1(day: &mut VM, out: &mut impl UIWrite) -> String {
+    out.info(format_args!("Disassembly:{}", Disassembly(&day.program)));
 
+    let mut vm = day.clone();
+    while !vm.halted() {
+        out.info(format_args!("{}", Trace(&vm)));
+        vm.step();
     }
+    vm.output.iter().map(|it| it.to_string()).collect::<Vec<_>>().join(",")
+}
 
-    format!("Part 1: {output_part_1:?}, Part 2: {i}")
+fn part2(day: VM, out: &mut impl UIWrite) -> OptionalAnswer<i64> {
+    let seed = find_quine_seed(&day, 0, 0);
+    if seed.is_none() {
+        out.info(format_args!("No value of register A makes the program output itself"));
+    }
+    OptionalAnswer(seed)
 }
 
-parsed_day!(nom_parsed_bytes(parse), solve);
+reporting_day!(nom_parsed_bytes(parse), part1, part2);
+
+#[cfg(test)]
+mod test {
+    use crate::day::day17::{parse, part1, part2};
+    use crate::day::nom_parsed_bytes;
+    use crate::ui::{Terse, UIFactory};
+
+    #[test]
+    fn example_part1() {
+        let mut vm = nom_parsed_bytes(parse)(b"Register A: 729\nRegister B: 0\nRegister C: 0\n\nProgram: 0,1,5,4,3,0\n").unwrap();
+        let mut sink = std::io::sink();
+        let mut out = Terse::create(&mut sink, "test");
+        assert_eq!("4,6,3,5,6,3,5,2,1,0", part1(&mut vm, &mut out));
+        assert_eq!(None, part2(vm, &mut out).0);
+    }
+
+    #[test]
+    fn example_part2() {
+        let vm = nom_parsed_bytes(parse)(b"Register A: 2024\nRegister B: 0\nRegister C: 0\n\nProgram: 0,3,5,4,3,0\n").unwrap();
+        let mut sink = std::io::sink();
+        assert_eq!(Some(117440), part2(vm, &mut Terse::create(&mut sink, "test")).0);
+    }
+}
@@ -1,5 +1,7 @@
-use crate::collections::{ArrayBag, CompassDirection, Index2D, Vec2D};
-use crate::day::nom_parsed_bytes;
+use crate::collections::grid_search::astar;
+use crate::collections::{Index2D, Vec2D};
+use crate::day::{nom_parsed_bytes, Part};
+use crate::parameters::{parameter, ParameterSpec};
 use crate::parse_helpers::parse_unsigned_nr_bytes;
 use crate::*;
 use nom::bytes::complete::tag;
@@ -7,14 +9,21 @@ use nom::character::complete::line_ending;
 use nom::multi::separated_list1;
 use nom::sequence::separated_pair;
 use nom::IResult;
-use pathfinding::prelude::astar;
 use nom::combinator::map;
 
+pub const PARAMETERS: &[ParameterSpec] = &[
+    ParameterSpec::new::<usize>("target"),
+    ParameterSpec::new::<usize>("fallen_bytes"),
+];
+
 struct Input {
     map: Vec2D<usize>,
-    list: Vec<Index2D>
+    list: Vec<Index2D>,
+    target: Index2D,
 }
 fn parse(input: &[u8]) -> IResult<&[u8], Input> {
+    let target_coordinate = parameter("target", 70, 6);
+    let target = Index2D { row: target_coordinate, column: target_coordinate };
     let (rest, list) = separated_list1(
         line_ending,
         map(separated_pair(
@@ -23,17 +32,15 @@ fn parse(input: &[u8]) -> IResult<&[u8], Input> {
             parse_unsigned_nr_bytes::<usize>,
         ), |(x, y)|Index2D { row: y, column: x }),
     )(input)?;
-    let mut map = Vec2D::new_from_flat(vec![usize::MAX; (TARGET.column + 1) * (TARGET.row + 1)], TARGET.column + 1);
+    let mut map = Vec2D::new_from_flat(vec![usize::MAX; (target.column + 1) * (target.row + 1)], target.column + 1);
 
     for (ts, idx) in list.iter().enumerate() {
         map[*idx] = ts;
     }
 
-    Ok((rest, Input { map, list }))
+    Ok((rest, Input { map, list, target }))
 }
 
-const TARGET: Index2D = Index2D { row: 70, column: 70 };
-
 struct State {
     input: Input,
     threshold: usize,
@@ -41,63 +48,34 @@ struct State {
 
 impl State {
     fn new(input: Input) -> Self {
-        Self { input, threshold: 1024 }
+        Self { input, threshold: parameter("fallen_bytes", 1024, 12) }
     }
 
     fn criticial_position(&self) -> usize {
         self.threshold - 1
     }
 
-    fn success(&self, idx: &Index2D) -> bool {
-        *idx == TARGET
-    }
-
-    fn heuristic(&self, idx: &Index2D) -> usize {
-        idx.manhattan_distance(TARGET)
-    }
-
     fn update_threshold(&mut self, path: &Vec<Index2D>) {
         let critical_index = path.iter().map(|idx|self.input.map[*idx]).min().unwrap();
         self.threshold = critical_index + 1;
     }
 
-    fn successors(&self, idx: &Index2D) -> impl IntoIterator<Item=(Index2D, usize)> {
-        let idx = *idx;
-        let mut bag = ArrayBag::<(Index2D, usize), 4>::default();
-        for direction in CompassDirection::ALL {
-            let stepped = idx + direction;
-            if stepped.plausible()
-                && stepped.row <= TARGET.row
-                && stepped.column <= TARGET.column
-            {
-                let value = self.input.map[stepped];
-                if value == usize::MAX || value >= self.threshold {
-                    bag.insert((stepped, 1));
-                }
-            }
-        }
-        bag
+    fn shortest_path(&self) -> Option<(Vec<Index2D>, usize)> {
+        astar(&self.input.map, Index2D::ZERO, self.input.target, |fallen_at| *fallen_at >= self.threshold)
     }
 }
 
-fn solve(input: Input) -> String {
+fn solve(input: Input, part: Part) -> (usize, String) {
     let mut state = State::new(input);
-    let (mut path, cost_1) = astar(
-        &Index2D::ZERO,
-        |idx|state.successors(idx),
-        |idx|state.heuristic(idx),
-        |idx|state.success(idx),
-    ).unwrap();
+    let (mut path, cost_1) = state.shortest_path().unwrap();
+    if !part.includes_part2() {
+        return (cost_1, String::new());
+    }
 
 
     loop {
         state.update_threshold(&path);
-        let next_path = astar(
-            &Index2D::ZERO,
-            |idx|state.successors(idx),
-            |idx|state.heuristic(idx),
-            |idx|state.success(idx),
-        );
+        let next_path = state.shortest_path();
 
         match next_path {
             Some((path2, _)) => {
@@ -109,7 +87,8 @@ fn solve(input: Input) -> String {
         }
     }
 
-    format!("{}, blocker at ({},{})", cost_1, state.input.list[state.criticial_position()].column, state.input.list[state.criticial_position()].row)
+    let blocker = state.input.list[state.criticial_position()];
+    (cost_1, format!("{},{}", blocker.column, blocker.row))
 }
 
-parsed_day!(nom_parsed_bytes(parse), solve);
+combined_day!(nom_parsed_bytes(parse), solve);
@@ -1,4 +1,4 @@
-use crate::ui::UIWrite;
+use crate::day::SimpleError;
 use crate::*;
 use fxhash::FxHashMap;
 use nom::bytes::complete::{tag, take_while1};
@@ -69,12 +69,12 @@ fn parse(input: &str) -> IResult<&str, OnsenPatterns> {
     ))
 }
 
-fn solve(input: &[u8], _out: &mut impl UIWrite) -> String {
-    let input = String::from_utf8_lossy(input);
-    let mut problem = parse(input.as_ref()).unwrap().1;
+fn parse_bytes(input: &[u8]) -> Result<OnsenPatterns<'_>, SimpleError> {
+    let input = std::str::from_utf8(input).map_err(|e| SimpleError(e.to_string()))?;
+    let (_, problem) = parse(input).map_err(|e| SimpleError(e.to_string()))?;
 
-    let (solvable, permutations) = problem.do_solve();
-    format!("Solvable: {solvable}, permutations {permutations}")
+    Ok(problem)
 }
 
-simple_day!(solve);
+// both parts come from the same construction counts, so there is nothing to skip
+combined_day!(parse_bytes, |mut problem: OnsenPatterns, _| problem.do_solve());
@@ -1,95 +1,67 @@
-use crate::collections::{CompassDirection, Index2D, Vec2D};
-use crate::day::parse_graphical_input_raw;
+use crate::collections::grid_search::{distances, UNREACHED};
+use crate::collections::{CompassDirection, Grid2D, Index2D, Vec2D};
+use crate::day::{parse_grid, GridError, Part};
+use crate::parameters::{parameter, ParameterSpec};
 use crate::*;
-use std::collections::{VecDeque};
+
+pub const PARAMETERS: &[ParameterSpec] = &[
+    ParameterSpec::new::<usize>("short_cheat_saving"),
+    ParameterSpec::new::<usize>("long_cheat_saving"),
+];
 
 #[derive(Debug)]
 struct Maze {
     is_wall_at: Vec2D<bool>,
-    cost_to_position: Vec2D<usize>,
     start: Index2D,
     end: Index2D,
 }
 
 
-fn parse(input: &[u8]) -> Result<Maze, !> {
-    let mut buffer = Vec::with_capacity(input.len());
-    let mut start = Index2D::IMPLAUSIBLE;
-    let mut end = Index2D::IMPLAUSIBLE;
-    let mut row_length = usize::MAX;
-    let mut length = 0;
-    parse_graphical_input_raw(input, |next, idx| {
-        match next {
-            b'\r' => return false,
-            b'\n' => {
-                row_length = length;
-                length = 0;
-                return true;
-            }
-            b'S' => {
-                start = idx;
-                buffer.push(false);
-            }
-            b'E' => {
-                end = idx;
-                buffer.push(false)
-            }
-            b'#' => buffer.push(true),
-            b'.' => buffer.push(false),
-            _ => unreachable!(),
-        }
-        length += 1;
-        false
-    });
-    let is_wall_at = Vec2D::new_from_flat(buffer, row_length);
-    let cost_to_position = Vec2D::new_from_flat(vec![usize::MAX; is_wall_at.len()], row_length);
+fn parse(input: &[u8]) -> Result<Maze, GridError> {
+    let parsed = parse_grid(input, b"SE", |byte| match byte {
+        b'#' => Some(true),
+        b'.' | b'S' | b'E' => Some(false),
+        _ => None,
+    })?;
 
     Ok(Maze {
-        is_wall_at,
-        start,
-        end,
-        cost_to_position,
+        start: parsed.marker(b'S')?,
+        end: parsed.marker(b'E')?,
+        is_wall_at: parsed.grid,
     })
 }
 
-fn solve(mut maze: Maze) -> String {
-    maze.cost_to_position[maze.end] = 0;
-    let mut queue = VecDeque::new();
-    queue.push_back(maze.end);
-
-    while let Some(next) = queue.pop_front() {
-        let cost = maze.cost_to_position[next];
-        for d in CompassDirection::ALL {
-            let step = next + d;
-            if !maze.is_wall_at[step] && maze.cost_to_position[step] == usize::MAX {
-                maze.cost_to_position[step] = cost + 1;
-                queue.push_back(step);
-            }
-        }
-    }
+fn solve(maze: Maze, part: Part) -> (usize, usize) {
+    let cost_to_position = distances(&maze.is_wall_at, [maze.end], |wall| !*wall);
 
+    let short_cheat_threshold = parameter("short_cheat_saving", 100, 1);
+    let long_cheat_threshold = parameter("long_cheat_saving", 100, 50);
     let mut target_buffer = [Index2D::IMPLAUSIBLE; 840];
     let mut cheating_options_long = 0;
     let mut cheating_options_short = 0;
 
-    for source in maze.cost_to_position.indices() {
-        let source_cost = maze.cost_to_position[source];
-        if source_cost == usize::MAX {
+    for source in cost_to_position.indices() {
+        let source_cost = cost_to_position[source];
+        if source_cost == UNREACHED {
             continue
         }
-        let targets = teleport_targets(source, &mut target_buffer, 20);
+        // the long cheats of part 2 cover the short ones, which are all part 1 needs
+        let targets = if part.includes_part2() {
+            teleport_targets::<20>(source, &mut target_buffer)
+        } else {
+            teleport_targets::<2>(source, &mut target_buffer)
+        };
         for target in targets {
             let jump_distance = source.manhattan_distance(*target);
-            if maze.cost_to_position.validate_index(*target) {
-                let target_cost = maze.cost_to_position[*target];
-                if target_cost != usize::MAX {
+            if let Some(&target_cost) = cost_to_position.get(*target) {
+                if target_cost != UNREACHED {
                     if target_cost < source_cost {
                         let saved = source_cost - target_cost - jump_distance;
-                        if saved >= 100 {
+                        if saved >= long_cheat_threshold {
                             cheating_options_long += 1;
-                            if jump_distance <= 2 {
-                                cheating_options_short += 1;
-                            }
+                        }
+                        if jump_distance <= 2 && saved >= short_cheat_threshold {
+                            cheating_options_short += 1;
                         }
                     }
                 }
@@ -97,10 +69,11 @@ fn solve(mut maze: Maze) -> String {
         }
     }
 
-    format!("Short: {cheating_options_short}, Long: {cheating_options_long}")
+    (cheating_options_short, cheating_options_long)
 }
 
-fn teleport_targets(source: Index2D, target: &mut [Index2D], n: usize) -> &[Index2D] {
+/** Tiles within `N` steps of the source, the cheat length being fixed at compile time to keep the loops tight */
+fn teleport_targets<const N: usize>(source: Index2D, target: &mut [Index2D]) -> &[Index2D] {
     let mut top = 0;
     let mut insert_if_plausible = |idx: Index2D| {
         if idx.plausible() {
@@ -109,12 +82,12 @@ fn teleport_targets(source: Index2D, target: &mut [Index2D], n: usize) -> &[Inde
         }
     };
 
-    for delta_1 in 1usize..=n {
+    for delta_1 in 1usize..=N {
         for d in CompassDirection::ALL {
             let base = source.move_by(delta_1, d);
             insert_if_plausible(base);
             let right = d.turn_right();
-            for delta_2 in 1usize..=(n - delta_1) {
+            for delta_2 in 1usize..=(N - delta_1) {
                 insert_if_plausible(base.move_by(delta_2, right));
             }
         }
@@ -123,4 +96,4 @@ fn teleport_targets(source: Index2D, target: &mut [Index2D], n: usize) -> &[Inde
     &target[..top]
 }
 
-parsed_day!(parse, solve);
+combined_day!(parse, solve);
@@ -1,17 +1,23 @@
-use std::fmt::format;
-use clap::Command;
-use nom::bytes::complete::take_while1;
-use nom::character::complete::one_of;
-use nom::Err::Error;
-use nom::{error_position, IResult};
-use nom::error::ErrorKind;
+use std::iter::{once, repeat_n};
+use nom::character::complete::{line_ending, one_of};
+use nom::combinator::map;
+use nom::multi::{many1, separated_list1};
+use nom::sequence::terminated;
+use nom::bytes::complete::tag;
+use nom::IResult;
 use crate::*;
+use crate::day::{nom_parsed_bytes, Part};
 use crate::day::day21::CommandPadKey::*;
-use crate::day::day21::NumPadKey::*;
 
 trait CommandEncoding<TargetAlphabet> {
     fn commands_to_input(&self, commands: &[TargetAlphabet]) -> Vec<CommandPadKey>;
 }
+
+/** Keys of a keypad, knowing the moves a robot arm needs between them */
+trait Key: Copy + Default {
+    fn move_to(self, target: Self) -> Move;
+}
+
 #[derive(Debug, Default, Copy, Clone)]
 #[repr(u8)]
 enum NumPadKey {
@@ -30,6 +36,7 @@ enum NumPadKey {
 }
 
 #[derive(Debug, Default, Copy, Clone)]
+#[repr(u8)]
 enum CommandPadKey {
     #[default]
     A,
@@ -39,8 +46,34 @@ enum CommandPadKey {
     Right,
 }
 
+impl NumPadKey {
+    const DIGITS: [NumPadKey; 10] = [NumPadKey::_0, NumPadKey::_1, NumPadKey::_2, NumPadKey::_3, NumPadKey::_4, NumPadKey::_5, NumPadKey::_6, NumPadKey::_7, NumPadKey::_8, NumPadKey::_9];
+}
+
+impl CommandPadKey {
+    const ALL: [CommandPadKey; 5] = [A, Up, Left, Down, Right];
+}
+
+impl Key for NumPadKey {
+    fn move_to(self, target: Self) -> Move {
+        SHORTEST_PATHS_ON_NUMPAD[self as usize][target as usize]
+    }
+}
+
+impl Key for CommandPadKey {
+    fn move_to(self, target: Self) -> Move {
+        SHORTEST_PATHS_ON_COMMANDPAD[self as usize][target as usize]
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
 struct CommandPadInput(CommandPadKey, usize);
 
+/**
+Shortest move between two keys: some amount of presses in (at most) two directions. Moves
+passing the gap of a keypad only work in the given order, all others may be reordered.
+*/
+#[derive(Debug, Copy, Clone)]
 struct Move {
     reorder_allowed: bool,
     input_1: CommandPadInput,
@@ -81,21 +114,20 @@ const fn down(n: usize) -> CommandPadInput {
 }
 
 const SHORTEST_PATHS_ON_NUMPAD: [[Move; 11]; 11] = const {
-    use CommandPadKey::*;
-    let from_A = [
+    let from_a = [
         zero(), // to A
         uni(left(1)), // to 0
 
         strict(up(1), left(2)), // to 1
-        strict(up(1), left(1)), // to 2
+        lax(up(1), left(1)), // to 2
         uni(up(1)), // to 3
 
         strict(up(2), left(2)), // to 4
-        strict(up(2), left(1)), // to 5
+        lax(up(2), left(1)), // to 5
         uni(up(2)), // to 6
 
         strict(up(3), left(2)), // to 7
-        strict(up(3), left(1)), // to 8
+        lax(up(3), left(1)), // to 8
         uni(up(3)), // to 9
     ];
     let from_0 = [
@@ -112,7 +144,7 @@ const SHORTEST_PATHS_ON_NUMPAD: [[Move; 11]; 11] = const {
 
         strict(up(3), left(1)), // to 7
         uni(up(3)), // to 8
-        lax(up(3), right(0)), // to 9
+        lax(up(3), right(1)), // to 9
     ];
     let from_1 = [
         strict(right(2), down(1)), // to A
@@ -168,9 +200,9 @@ const SHORTEST_PATHS_ON_NUMPAD: [[Move; 11]; 11] = const {
         strict(right(2), down(2)), // to A
         strict(right(1), down(2)), // to 0
 
-        lax(left(1), down(1)), // to 1
-        uni(down(1)), // to 2
-        lax(right(1), down(1)), // to 3
+        uni(down(1)), // to 1
+        lax(right(1), down(1)), // to 2
+        lax(right(2), down(1)), // to 3
 
         zero(), // to 4
         uni(right(1)), // to 5
@@ -181,7 +213,7 @@ const SHORTEST_PATHS_ON_NUMPAD: [[Move; 11]; 11] = const {
         lax(up(1), right(2)), // to 9
     ];
     let from_5 = [
-        strict(right(1), down(2)), // to A
+        lax(right(1), down(2)), // to A
         uni(down(2)), // to 0
 
         lax(left(1), down(1)), // to 1
@@ -214,61 +246,226 @@ const SHORTEST_PATHS_ON_NUMPAD: [[Move; 11]; 11] = const {
         uni(up(1)), // to 9
     ];
 
-
-    // TODO FROM HERE
     let from_7 = [
-        strict(right(2), down(2)), // to A
-        strict(right(1), down(2)), // to 0
+        strict(right(2), down(3)), // to A
+        strict(right(1), down(3)), // to 0
 
-        lax(left(1), down(1)), // to 1
-        uni(down(1)), // to 2
-        lax(right(1), down(1)), // to 3
+        uni(down(2)), // to 1
+        lax(right(1), down(2)), // to 2
+        lax(right(2), down(2)), // to 3
 
-        zero(), // to 4
-        uni(right(1)), // to 5
-        uni(right(2)), // to 6
+        uni(down(1)), // to 4
+        lax(right(1), down(1)), // to 5
+        lax(right(2), down(1)), // to 6
 
-        uni(up(1)), // to 7
-        lax(up(1), right(1)), // to 8
-        lax(up(1), right(2)), // to 9
+        zero(), // to 7
+        uni(right(1)), // to 8
+        uni(right(2)), // to 9
     ];
     let from_8 = [
-        strict(right(1), down(2)), // to A
-        uni(down(2)), // to 0
+        lax(right(1), down(3)), // to A
+        uni(down(3)), // to 0
 
-        lax(left(1), down(1)), // to 1
-        uni(down(1)), // to 2
-        lax(right(1), down(1)), // to 3
+        lax(left(1), down(2)), // to 1
+        uni(down(2)), // to 2
+        lax(right(1), down(2)), // to 3
 
-        uni(left(1)), // to 4
-        zero(), // to 5
-        uni(right(1)), // to 6
+        lax(left(1), down(1)), // to 4
+        uni(down(1)), // to 5
+        lax(right(1), down(1)), // to 6
 
-        lax(up(1), left(1)), // to 7
-        uni(up(1)), // to 8
-        lax(up(1), right(1)), // to 9
+        uni(left(1)), // to 7
+        zero(), // to 8
+        uni(right(1)), // to 9
     ];
 
     let from_9 = [
-        uni(down(2)), // to A
-        lax(left(1), down(2)), // to 0
+        uni(down(3)), // to A
+        lax(left(1), down(3)), // to 0
 
-        lax(left(2), down(1)), // to 1
-        lax(down(1), left(1)), // to 2
-        uni(down(1)), // to 3
+        lax(left(2), down(2)), // to 1
+        lax(left(1), down(2)), // to 2
+        uni(down(2)), // to 3
 
-        uni(left(2)), // to 4
-        uni(left(1)), // to 5
-        zero(), // to 6
+        lax(left(2), down(1)), // to 4
+        lax(left(1), down(1)), // to 5
+        uni(down(1)), // to 6
 
-        lax(up(1), left(2)), // to 7
-        lax(up(1), left(1)), // to 8
-        uni(up(1)), // to 9
+        uni(left(2)), // to 7
+        uni(left(1)), // to 8
+        zero(), // to 9
+    ];
+
+    [from_a, from_0, from_1, from_2, from_3, from_4, from_5, from_6, from_7, from_8, from_9]
+};
+
+const SHORTEST_PATHS_ON_COMMANDPAD: [[Move; 5]; 5] = const {
+    let from_a = [
+        zero(), // to A
+        uni(left(1)), // to Up
+        strict(down(1), left(2)), // to Left
+        lax(left(1), down(1)), // to Down
+        uni(down(1)), // to Right
+    ];
+    let from_up = [
+        uni(right(1)), // to A
+        zero(), // to Up
+        strict(down(1), left(1)), // to Left
+        uni(down(1)), // to Down
+        lax(down(1), right(1)), // to Right
+    ];
+    let from_left = [
+        strict(right(2), up(1)), // to A
+        strict(right(1), up(1)), // to Up
+        zero(), // to Left
+        uni(right(1)), // to Down
+        uni(right(2)), // to Right
+    ];
+    let from_down = [
+        lax(right(1), up(1)), // to A
+        uni(up(1)), // to Up
+        uni(left(1)), // to Left
+        zero(), // to Down
+        uni(right(1)), // to Right
+    ];
+    let from_right = [
+        uni(up(1)), // to A
+        lax(left(1), up(1)), // to Up
+        uni(left(2)), // to Left
+        uni(left(1)), // to Down
+        zero(), // to Right
     ];
 
-    [from_A, from_0, from_1, from_2, from_3, from_4, from_5, from_6, from_7, from_8, from_9]
+    [from_a, from_up, from_left, from_down, from_right]
 };
 
+impl Move {
+    /** The command pad presses performing the move and pressing the target key, in all allowed orders */
+    fn orderings(self) -> impl Iterator<Item=impl Iterator<Item=CommandPadKey>> {
+        let presses = |first: CommandPadInput, second: CommandPadInput| {
+            repeat_n(first.0, first.1).chain(repeat_n(second.0, second.1)).chain(once(A))
+        };
+        let reordered = self.reorder_allowed && self.input_1.1 > 0 && self.input_2.1 > 0;
+
+        once(presses(self.input_1, self.input_2))
+            .chain(once(presses(self.input_2, self.input_1)).filter(move |_| reordered))
+    }
+}
+
+/** Costs (in key presses by the human) of pressing a command pad key after another */
+type PressCosts = [[usize; 5]; 5];
+
+fn sequence_cost(costs: &PressCosts, sequence: impl IntoIterator<Item=CommandPadKey>) -> usize {
+    let mut position = A;
+    sequence.into_iter().map(|key| {
+        let cost = costs[position as usize][key as usize];
+        position = key;
+        cost
+    }).sum()
+}
+
+/**
+Press costs for each depth of robots between the human and a command pad. The human presses
+keys directly, at every further robot layer the cheapest ordering of each move wins.
+*/
+fn press_costs_per_depth(max_depth: usize) -> Vec<PressCosts> {
+    let mut per_depth = vec![[[1; 5]; 5]];
+    for depth in 1..=max_depth {
+        let mut costs = [[0; 5]; 5];
+        for from in CommandPadKey::ALL {
+            for to in CommandPadKey::ALL {
+                costs[from as usize][to as usize] = from.move_to(to).orderings()
+                    .map(|ordering| sequence_cost(&per_depth[depth - 1], ordering))
+                    .min()
+                    .unwrap();
+            }
+        }
+        per_depth.push(costs);
+    }
+
+    per_depth
+}
+
+/** Robot operated through a command pad, with the costs of the presses on that pad */
+struct Robot<'a> {
+    costs: &'a PressCosts,
+}
+
+impl<K: Key> CommandEncoding<K> for Robot<'_> {
+    fn commands_to_input(&self, commands: &[K]) -> Vec<CommandPadKey> {
+        let mut position = K::default();
+        let mut input = Vec::new();
+        for target in commands.iter().copied() {
+            let cheapest = position.move_to(target).orderings()
+                .map(|ordering| ordering.collect::<Vec<_>>())
+                .min_by_key(|ordering| sequence_cost(self.costs, ordering.iter().copied()))
+                .unwrap();
+            input.extend(cheapest);
+            position = target;
+        }
+
+        input
+    }
+}
+
+#[derive(Debug)]
+struct Code {
+    keys: Vec<NumPadKey>,
+    value: usize,
+}
+
+fn parse_code(input: &[u8]) -> IResult<&[u8], Code> {
+    map(terminated(many1(one_of("0123456789")), tag(b"A")), |digits| {
+        let mut keys = Vec::with_capacity(digits.len() + 1);
+        let mut value = 0;
+        for digit in digits {
+            let digit = digit.to_digit(10).unwrap() as usize;
+            keys.push(NumPadKey::DIGITS[digit]);
+            value = value * 10 + digit;
+        }
+        keys.push(NumPadKey::_A);
+        Code { keys, value }
+    })(input)
+}
+
+fn complexity(codes: &[Code], costs: &PressCosts) -> usize {
+    let robot = Robot { costs };
+    codes.iter().map(|code| {
+        let length = sequence_cost(costs, robot.commands_to_input(&code.keys));
+        length * code.value
+    }).sum()
+}
+
+fn solve(codes: Vec<Code>, part: Part) -> (usize, usize) {
+    if !part.includes_part2() {
+        return (complexity(&codes, &press_costs_per_depth(2)[2]), 0);
+    }
+
+    let costs = press_costs_per_depth(25);
+    let part1 = if part.includes_part1() { complexity(&codes, &costs[2]) } else { 0 };
+    (part1, complexity(&codes, &costs[25]))
+}
+
+combined_day!(nom_parsed_bytes(separated_list1(line_ending, parse_code)), solve);
+
+#[cfg(test)]
+mod test {
+    use nom::character::complete::line_ending;
+    use nom::multi::separated_list1;
+    use crate::day::day21::{parse_code, solve, Code};
+    use crate::day::{nom_parsed_bytes, Part};
 
+    fn example() -> Vec<Code> {
+        nom_parsed_bytes(separated_list1(line_ending, parse_code))(b"029A\n980A\n179A\n456A\n379A\n").unwrap()
+    }
 
-unimplemented_day!();
\ No newline at end of file
+    #[test]
+    fn example_part1() {
+        assert_eq!(126384, solve(example(), Part::One).0);
+    }
+
+    #[test]
+    fn example_both_parts() {
+        assert_eq!((126384, 154115708116294), solve(example(), Part::Both));
+    }
+}
@@ -1,4 +1,3 @@
-use std::fmt::{Display, Formatter};
 use crate::parse_helpers::parse_unsigned_nr_bytes;
 use crate::*;
 use nom::character::complete::line_ending;
@@ -46,12 +45,6 @@ impl Default for Solution {
     }
 }
 
-impl Display for Solution {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        f.write_fmt(format_args!("Sum = {}, Max profit = {}", self.sum, self.best_profit))
-    }
-}
-
 fn price(n: usize) -> u8 {
     (n % 10) as u8
 }
@@ -85,5 +78,6 @@ fn monkey_nr_sum(data: &mut Solution, mut next: usize) {
 
 streaming_day!(
     terminated(parse_unsigned_nr_bytes::<usize>, line_ending),
-    monkey_nr_sum
+    monkey_nr_sum,
+    |solution: Solution| (solution.sum, solution.best_profit)
 );
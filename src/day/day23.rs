@@ -6,87 +6,280 @@ use nom::IResult;
 use nom::multi::fold_many1;
 use nom::sequence::{separated_pair, terminated};
 use crate::*;
+use crate::day::SimpleError;
 
+/** Set of interned nodes, one bit per node id */
+#[derive(Debug, Clone)]
+struct NodeSet(Vec<u64>);
 
-#[derive(Debug, Default)]
-struct WorkingData<'a> {
-    edges: FxHashMap<&'a str, FxHashSet<&'a str>>,
-    three_cliques: FxHashSet<[&'a str; 3]>,
+impl NodeSet {
+    fn empty(nodes: usize) -> Self {
+        Self(vec![0; nodes.div_ceil(64)])
+    }
+
+    fn insert(&mut self, node: usize) {
+        self.0[node / 64] |= 1 << (node % 64);
+    }
+
+    fn remove(&mut self, node: usize) {
+        self.0[node / 64] &= !(1 << (node % 64));
+    }
+
+    fn is_empty(&self) -> bool {
+        self.0.iter().all(|word| *word == 0)
+    }
+
+    fn len(&self) -> usize {
+        self.0.iter().map(|word| word.count_ones() as usize).sum()
+    }
+
+    fn combine(&self, other: &NodeSet, combine: impl Fn(u64, u64) -> u64) -> NodeSet {
+        NodeSet(self.0.iter().zip(&other.0).map(|(a, b)| combine(*a, *b)).collect())
+    }
+
+    fn intersection(&self, other: &NodeSet) -> NodeSet {
+        self.combine(other, |a, b| a & b)
+    }
+
+    fn difference(&self, other: &NodeSet) -> NodeSet {
+        self.combine(other, |a, b| a & !b)
+    }
+
+    fn intersection_len(&self, other: &NodeSet) -> usize {
+        self.0.iter().zip(&other.0).map(|(a, b)| (a & b).count_ones() as usize).sum()
+    }
+
+    fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        self.0.iter().enumerate().flat_map(|(index, word)| {
+            let mut word = *word;
+            std::iter::from_fn(move || {
+                if word == 0 {
+                    None
+                } else {
+                    let bit = word.trailing_zeros() as usize;
+                    word &= word - 1;
+                    Some(index * 64 + bit)
+                }
+            })
+        })
+    }
 }
 
-fn parse_input(input: &str) -> IResult<&str, WorkingData> {
-    let parse_single_line = separated_pair(alpha1, tag("-"), alpha1);
-    fold_many1(terminated(parse_single_line, line_ending), WorkingData::default, |mut result, (a, b)| {
-        result.edges.entry(a).or_default().insert(b);
-        result.edges.entry(b).or_default().insert(a);
-        result
-    })(input)
+/** Network with the computer names interned as ids, in alphabetical order */
+#[derive(Debug)]
+struct Network<'a> {
+    names: Vec<&'a str>,
+    neighbours: Vec<NodeSet>,
 }
 
-fn part1(input: &mut WorkingData) -> usize {
-    for (first, neighbours) in input.edges.iter() {
-        if first.starts_with('t') {
-            for second in neighbours {
-                let neighbours_of_neighbour = input.edges.get(second).unwrap();
-                for third in neighbours.intersection(neighbours_of_neighbour) {
-                    let mut clique_code = [*first, *second, *third];
-                    clique_code.sort();
-                    input.three_cliques.insert(clique_code);
-                }
+impl<'a> Network<'a> {
+    fn new(edges: FxHashMap<&'a str, FxHashSet<&'a str>>) -> Self {
+        let mut names: Vec<_> = edges.keys().copied().collect();
+        names.sort();
+        let ids: FxHashMap<_, _> = names.iter().enumerate().map(|(id, name)| (*name, id)).collect();
+
+        let neighbours = names.iter().map(|name| {
+            let mut set = NodeSet::empty(names.len());
+            for neighbour in &edges[name] {
+                set.insert(ids[neighbour]);
             }
-        }
+            set
+        }).collect();
+
+        Self { names, neighbours }
     }
 
-    input.three_cliques.len()
-}
+    fn all_nodes(&self) -> NodeSet {
+        let mut all = NodeSet::empty(self.names.len());
+        for node in 0..self.names.len() {
+            all.insert(node);
+        }
+        all
+    }
 
-fn find_extension_node<'a, 'b>(clique: &'b FxHashSet<&'a str>, edges: &FxHashMap<&'a str, FxHashSet<&'a str>>) -> Option<&'a str> {
-    let one_node = clique.iter().next().unwrap();
-    let potential_members = edges.get(one_node).unwrap();
-    for grow_candidate in potential_members {
-        if clique.iter().copied().all(|node| {
-            edges.get(node).unwrap().contains(grow_candidate)
-        }) {
-            // all current clique members contain the node, add it
-            return Some(grow_candidate);
+    /** Nodes ordered by repeatedly removing one of least remaining degree */
+    fn degeneracy_ordering(&self) -> Vec<usize> {
+        let mut remaining = self.all_nodes();
+        let mut ordering = Vec::with_capacity(self.names.len());
+        while let Some(node) = remaining.iter().min_by_key(|node| self.neighbours[*node].intersection_len(&remaining)) {
+            remaining.remove(node);
+            ordering.push(node);
         }
+        ordering
     }
 
-    None
-}
+    /**
+    Bron–Kerbosch with pivoting: extends `clique` by the `candidates`, skipping the nodes in
+    `excluded` that were already tried. Branches that cannot beat `largest` are cut off.
+    */
+    fn extend_clique(&self, clique: &mut Vec<usize>, mut candidates: NodeSet, mut excluded: NodeSet, largest: &mut Vec<usize>) {
+        if candidates.is_empty() {
+            if excluded.is_empty() && clique.len() > largest.len() {
+                *largest = clique.clone();
+            }
+            return;
+        }
+        if clique.len() + candidates.len() <= largest.len() {
+            return;
+        }
 
-fn part2(input: WorkingData) -> String {
-    let mut maximal_cliques = Vec::<FxHashSet<&str>>::default();
-    let mut largest_set = FxHashSet::default();
+        let pivot = candidates.iter().chain(excluded.iter())
+            .max_by_key(|node| self.neighbours[*node].intersection_len(&candidates))
+            .unwrap();
 
-    for root in input.three_cliques {
-        let mut clique_set = FxHashSet::from_iter(root.iter().copied());
-        if maximal_cliques.iter().any(|maximum_clique| maximum_clique.is_superset(&clique_set)) {
-            continue;
+        for node in candidates.difference(&self.neighbours[pivot]).iter() {
+            let neighbours = &self.neighbours[node];
+            clique.push(node);
+            self.extend_clique(clique, candidates.intersection(neighbours), excluded.intersection(neighbours), largest);
+            clique.pop();
+            candidates.remove(node);
+            excluded.insert(node);
         }
+    }
+
+    fn maximum_clique(&self) -> Vec<usize> {
+        let mut largest = Vec::new();
+        let mut candidates = self.all_nodes();
+        let mut excluded = NodeSet::empty(self.names.len());
 
-        while let Some(extension) = find_extension_node(&clique_set, &input.edges) {
-            clique_set.insert(extension);
+        for node in self.degeneracy_ordering() {
+            let neighbours = &self.neighbours[node];
+            self.extend_clique(&mut vec![node], candidates.intersection(neighbours), excluded.intersection(neighbours), &mut largest);
+            candidates.remove(node);
+            excluded.insert(node);
         }
 
-        if clique_set.len() > largest_set.len() {
-            largest_set = clique_set.clone();
+        largest.sort();
+        largest
+    }
+}
+
+fn parse_input(input: &str) -> IResult<&str, FxHashMap<&str, FxHashSet<&str>>> {
+    let parse_single_line = separated_pair(alpha1, tag("-"), alpha1);
+    fold_many1(terminated(parse_single_line, line_ending), FxHashMap::<_, FxHashSet<_>>::default, |mut edges, (a, b)| {
+        edges.entry(a).or_default().insert(b);
+        edges.entry(b).or_default().insert(a);
+        edges
+    })(input)
+}
+
+fn part1(network: &mut Network) -> usize {
+    let historian_candidate = |node: usize| network.names[node].starts_with('t');
+    let mut count = 0;
+
+    for (first, neighbours) in network.neighbours.iter().enumerate() {
+        for second in neighbours.iter().filter(|second| *second > first) {
+            let common = neighbours.intersection(&network.neighbours[second]);
+            for third in common.iter().filter(|third| *third > second) {
+                if [first, second, third].into_iter().any(historian_candidate) {
+                    count += 1;
+                }
+            }
         }
-        maximal_cliques.push(clique_set);
     }
 
-    let mut sorted: Vec<_> = largest_set.iter().copied().collect();
-    sorted.sort();
-    sorted.join(",")
+    count
+}
+
+fn part2(network: Network) -> String {
+    network.maximum_clique().into_iter()
+        .map(|node| network.names[node])
+        .collect::<Vec<_>>()
+        .join(",")
 }
 
-fn solve(input: &[u8]) -> String {
-    let input_string = String::from_utf8_lossy(input);
-    let mut parsed = parse_input(input_string.as_ref()).unwrap().1;
-    let part1 = part1(&mut parsed);
-    let part2 = part2(parsed);
+fn parse(input: &[u8]) -> Result<Network<'_>, SimpleError> {
+    let input = std::str::from_utf8(input).map_err(|e| SimpleError(e.to_string()))?;
+    let (_, edges) = parse_input(input).map_err(|e| SimpleError(e.to_string()))?;
 
-    format!("Part 1: {part1}, Part 2: {part2}")
+    Ok(Network::new(edges))
 }
 
-simple_day!(|x|solve(x));
\ No newline at end of file
+parsed_day!(parse, part1, part2);
+
+#[cfg(test)]
+mod test {
+    use crate::day::day23::{parse, part1, part2, Network, NodeSet};
+
+    const EXAMPLE: &[u8] = b"kh-tc\nqp-kh\nde-cg\nka-co\nyn-aq\nqp-ub\ncg-tb\nvc-aq\ntb-ka\nwh-tc\nyn-cg\nkh-ub\nta-co\nde-co\ntc-td\ntb-wq\nwh-td\nta-ka\ntd-qp\naq-cg\nwq-ub\nub-vc\nde-ta\nwq-aq\nwq-vc\nwh-yn\nka-de\nkh-ta\nco-tc\nwh-qp\ntb-vc\ntd-yn\n";
+
+    /** Triangle a, b, c with d hanging off c */
+    fn triangle() -> Network<'static> {
+        parse(b"a-b\na-c\nb-c\nc-d\n").unwrap()
+    }
+
+    fn set(nodes: &[usize]) -> NodeSet {
+        let mut set = NodeSet::empty(130);
+        for node in nodes {
+            set.insert(*node);
+        }
+        set
+    }
+
+    #[test]
+    fn example_part1() {
+        assert_eq!(7, part1(&mut parse(EXAMPLE).unwrap()));
+    }
+
+    #[test]
+    fn example_part2() {
+        assert_eq!("co,de,ka,ta", part2(parse(EXAMPLE).unwrap()));
+    }
+
+    #[test]
+    fn node_set_across_words() {
+        let mut nodes = set(&[0, 63, 64, 129]);
+        assert_eq!(4, nodes.len());
+        assert_eq!(vec![0, 63, 64, 129], nodes.iter().collect::<Vec<_>>());
+
+        nodes.remove(63);
+        nodes.remove(63);
+        assert_eq!(vec![0, 64, 129], nodes.iter().collect::<Vec<_>>());
+        assert!(!nodes.is_empty());
+        assert!(NodeSet::empty(130).is_empty());
+    }
+
+    #[test]
+    fn node_set_operations() {
+        let left = set(&[1, 64, 100]);
+        let right = set(&[64, 100, 128]);
+
+        assert_eq!(vec![64, 100], left.intersection(&right).iter().collect::<Vec<_>>());
+        assert_eq!(2, left.intersection_len(&right));
+        assert_eq!(vec![1], left.difference(&right).iter().collect::<Vec<_>>());
+        assert_eq!(vec![128], right.difference(&left).iter().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn extend_clique_finds_largest() {
+        let network = triangle();
+        let mut largest = Vec::new();
+        network.extend_clique(&mut Vec::new(), network.all_nodes(), NodeSet::empty(4), &mut largest);
+
+        largest.sort();
+        assert_eq!(vec![0, 1, 2], largest);
+    }
+
+    #[test]
+    fn extend_clique_skips_cliques_extendable_by_excluded() {
+        let network = triangle();
+        let mut largest = Vec::new();
+        let mut excluded = NodeSet::empty(4);
+        excluded.insert(2);
+        let mut candidates = NodeSet::empty(4);
+        candidates.insert(0);
+        candidates.insert(1);
+
+        network.extend_clique(&mut Vec::new(), candidates, excluded, &mut largest);
+        assert!(largest.is_empty());
+    }
+
+    #[test]
+    fn extend_clique_keeps_larger_result() {
+        let network = triangle();
+        let mut largest = vec![7, 8, 9];
+        network.extend_clique(&mut Vec::new(), network.all_nodes(), NodeSet::empty(4), &mut largest);
+
+        assert_eq!(vec![7, 8, 9], largest);
+    }
+}
@@ -1,23 +1,30 @@
 use crate::*;
-use std::borrow::Cow;
-use std::fmt::{format, Debug, Formatter};
+use std::error::Error;
+use std::fmt::{Debug, Display, Formatter};
+use std::fs::write;
+use std::path::PathBuf;
 use fxhash::FxHashMap;
 use nom::branch::alt;
-use nom::bytes::complete::{tag, take_while1};
-use nom::character::complete::{alphanumeric1, line_ending, one_of};
+use nom::bytes::complete::tag;
+use nom::character::complete::{alphanumeric1, line_ending};
 use nom::combinator::{map, value};
 use nom::IResult;
 use nom::multi::separated_list1;
 use nom::sequence::{separated_pair, tuple};
-use crate::*;
-use crate::day::nom_parsed_bytes;
+use crate::day::{nom_parsed_bytes, SimpleError};
+use crate::parameters::{optional_parameter, ParameterSpec};
+use crate::ui::UIWrite;
+
+pub const PARAMETERS: &[ParameterSpec] = &[
+    ParameterSpec::new::<PathBuf>("dot_file"),
+];
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 enum Operation {
     AND, OR, XOR
 }
 
-#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 struct Label<'a>(&'a[u8]);
 impl Debug for Label<'_> {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
@@ -25,6 +32,37 @@ impl Debug for Label<'_> {
     }
 }
 
+impl Display for Label<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        Debug::fmt(self, f)
+    }
+}
+
+impl Label<'_> {
+    fn is_input(self) -> bool {
+        self.0.starts_with(b"x") || self.0.starts_with(b"y")
+    }
+
+    fn is_output(self) -> bool {
+        self.0.starts_with(b"z")
+    }
+
+    /** Bit position of a numbered wire like `x07` */
+    fn bit(self) -> Option<usize> {
+        std::str::from_utf8(&self.0[1..]).ok()?.parse().ok()
+    }
+}
+
+impl Operation {
+    fn apply(self, input_1: bool, input_2: bool) -> bool {
+        match self {
+            Operation::AND => input_1 & input_2,
+            Operation::OR => input_1 | input_2,
+            Operation::XOR => input_1 ^ input_2,
+        }
+    }
+}
+
 #[derive(Debug)]
 struct Gate<'a> {
     operation: Operation,
@@ -39,6 +77,126 @@ struct Input<'a> {
     value: bool
 }
 
+#[derive(Debug)]
+struct Circuit<'a> {
+    input_by_label: FxHashMap<Label<'a>, Input<'a>>,
+    gate_by_output_label: FxHashMap<Label<'a>, Gate<'a>>,
+    /** Gate outputs ordered so that every gate comes after the gates driving its inputs */
+    evaluation_order: Vec<Label<'a>>,
+}
+
+#[derive(Debug)]
+enum CircuitError<'a> {
+    Undriven { wire: Label<'a>, gate: Label<'a> },
+    /** Wires in the order they drive each other */
+    Cycle(Vec<Label<'a>>),
+}
+
+impl Display for CircuitError<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CircuitError::Undriven { wire, gate } =>
+                f.write_fmt(format_args!("Wire {wire} feeding the gate for {gate} is neither an input nor a gate output")),
+            CircuitError::Cycle(wires) => {
+                let wires = wires.iter().map(|label| label.to_string()).collect::<Vec<_>>().join(" -> ");
+                f.write_fmt(format_args!("Wires {wires} form a cycle"))
+            }
+        }
+    }
+}
+
+impl Error for CircuitError<'_> {}
+
+/**
+Every gate left unresolved has at least one unresolved input driven by another such gate. Walking
+those inputs backwards has to revisit a wire eventually, closing the cycle.
+*/
+fn find_cycle<'a>(gates: &FxHashMap<Label<'a>, Gate<'a>>, unresolved_inputs: &FxHashMap<Label<'a>, usize>) -> Vec<Label<'a>> {
+    let blocked = |label: &Label| unresolved_inputs.get(label).is_some_and(|unresolved| *unresolved > 0);
+    let mut path = vec![*unresolved_inputs.keys().filter(|label| blocked(label)).min().unwrap()];
+
+    loop {
+        let gate = &gates[path.last().unwrap()];
+        let next = if blocked(&gate.input_1) { gate.input_1 } else { gate.input_2 };
+        if let Some(start) = path.iter().position(|label| *label == next) {
+            let mut cycle = path.split_off(start);
+            cycle.reverse();
+            return cycle;
+        }
+        path.push(next);
+    }
+}
+
+impl<'a> Circuit<'a> {
+    fn new(inputs: Vec<Input<'a>>, gates: Vec<Gate<'a>>) -> Result<Self, CircuitError<'a>> {
+        let input_by_label: FxHashMap<_, _> = inputs.into_iter().map(|input| (input.label, input)).collect();
+        let gate_by_output_label: FxHashMap<_, _> = gates.into_iter().map(|g| (g.output, g)).collect();
+
+        let mut unresolved_inputs = FxHashMap::default();
+        let mut consumers = FxHashMap::<_, Vec<_>>::default();
+        for gate in gate_by_output_label.values() {
+            for wire in [gate.input_1, gate.input_2] {
+                if !input_by_label.contains_key(&wire) && !gate_by_output_label.contains_key(&wire) {
+                    return Err(CircuitError::Undriven { wire, gate: gate.output });
+                }
+                consumers.entry(wire).or_default().push(gate.output);
+                *unresolved_inputs.entry(gate.output).or_insert(0) += 1;
+            }
+        }
+
+        let mut resolved: Vec<_> = input_by_label.keys().copied().collect();
+        let mut evaluation_order = Vec::with_capacity(gate_by_output_label.len());
+        while let Some(wire) = resolved.pop() {
+            for consumer in consumers.get(&wire).into_iter().flatten() {
+                let unresolved = unresolved_inputs.get_mut(consumer).unwrap();
+                *unresolved -= 1;
+                if *unresolved == 0 {
+                    evaluation_order.push(*consumer);
+                    resolved.push(*consumer);
+                }
+            }
+        }
+
+        if evaluation_order.len() < gate_by_output_label.len() {
+            return Err(CircuitError::Cycle(find_cycle(&gate_by_output_label, &unresolved_inputs)));
+        }
+
+        Ok(Self { input_by_label, gate_by_output_label, evaluation_order })
+    }
+
+    /** Number formed by the initial values of the input wires starting with `prefix` */
+    fn operand(&self, prefix: u8) -> u64 {
+        self.input_by_label.values()
+            .filter(|input| input.value && input.label.0.starts_with(&[prefix]))
+            .filter_map(|input| input.label.bit())
+            .fold(0, |operand, bit| operand | 1 << bit)
+    }
+
+    /** Evaluates the z wires with the x and y wires set to the bits of `x` and `y` */
+    fn evaluate(&self, x: u64, y: u64) -> u64 {
+        let mut values = FxHashMap::default();
+        for label in self.input_by_label.keys() {
+            let operand = if label.0.starts_with(b"x") { x } else { y };
+            let value = label.bit().is_some_and(|bit| bit < u64::BITS as usize && operand & 1 << bit != 0);
+            values.insert(*label, value);
+        }
+
+        let mut result = 0;
+        for output in &self.evaluation_order {
+            let gate = &self.gate_by_output_label[output];
+            let value = gate.operation.apply(values[&gate.input_1], values[&gate.input_2]);
+            values.insert(*output, value);
+            if value && output.is_output() {
+                if let Some(bit) = output.bit() {
+                    result |= 1 << bit;
+                }
+            }
+        }
+
+        result
+    }
+}
+
 fn parse_label(input: &[u8]) -> IResult<&[u8], Label> {
     map(alphanumeric1, |bytes| Label(bytes))(input)
 }
@@ -61,48 +219,188 @@ fn parse_gate(input: &[u8]) -> IResult<&[u8], Gate> {
     Ok((rest, Gate { operation, input_1, input_2, output, }))
 }
 
-fn parse(input: &[u8]) -> IResult<&[u8], (Vec<Input>, Vec<Gate>)> {
+fn parse_wiring(input: &[u8]) -> IResult<&[u8], (Vec<Input<'_>>, Vec<Gate<'_>>)> {
     separated_pair(separated_list1(line_ending, parse_input), tuple((line_ending, line_ending)), separated_list1(line_ending, parse_gate))(input)
 }
 
-fn solve((inputs, gates): (Vec<Input>, Vec<Gate>)) -> usize {
-    let gate_by_output_label: FxHashMap<_, _> = gates.into_iter().map(|g| {
-        (g.output, g)
-    }).collect();
+fn parse(input: &[u8]) -> Result<Circuit, SimpleError> {
+    let (inputs, gates) = nom_parsed_bytes(parse_wiring)(input)?;
+    Circuit::new(inputs, gates).map_err(|e| SimpleError(e.to_string()))
+}
+
+fn part1(circuit: &mut Circuit) -> u64 {
+    circuit.evaluate(circuit.operand(b'x'), circuit.operand(b'y'))
+}
+
+/**
+Checks the gates against the roles they play in a ripple-carry adder. For every bit `n` above 0:
+- `xn XOR yn` is the half sum, feeding the XOR producing `zn`
+- `xn AND yn` and `carry AND half sum` are partial carries, feeding the OR producing the carry
+- only the XOR of half sum and carry drives `zn`, with the final carry directly driving the highest z
+Bit 0 is a plain half adder. Gates violating their role have their output swapped.
+*/
+fn swapped_outputs<'a>(circuit: &Circuit<'a>) -> Vec<Label<'a>> {
+    let gates = || circuit.gate_by_output_label.values();
+    let highest_output = gates().map(|gate| gate.output).filter(|label| label.is_output()).max();
+    let feeds = |label: Label, operation: Operation| {
+        gates().any(|gate| gate.operation == operation && (gate.input_1 == label || gate.input_2 == label))
+    };
+
+    let mut swapped: Vec<_> = gates().filter(|gate| {
+        let from_inputs = gate.input_1.is_input() && gate.input_2.is_input();
+        let first_bit = from_inputs && gate.input_1.0.ends_with(b"00");
+        let role_violated = match gate.operation {
+            Operation::XOR if first_bit => !gate.output.is_output(),
+            Operation::XOR if from_inputs => !feeds(gate.output, Operation::XOR),
+            Operation::XOR => !gate.output.is_output(),
+            Operation::AND if first_bit => false,
+            Operation::AND => !feeds(gate.output, Operation::OR),
+            Operation::OR => false,
+        };
+        let output_violated = if Some(gate.output) == highest_output {
+            gate.operation != Operation::OR
+        } else {
+            gate.output.is_output() && gate.operation != Operation::XOR
+        };
 
-    let input_by_label: FxHashMap<_, _> = inputs.into_iter().map(|input| (input.label, input)).collect();
+        role_violated || output_violated
+    }).map(|gate| gate.output).collect();
 
-    let mut result = 0;
-    let mut shift = 0;
-    let mut key = format!("z{shift:>02}");
-    while let Some(gate) = gate_by_output_label.get(&Label(key.as_bytes())) {
-        let bit = calculate(&gate_by_output_label, &input_by_label, gate);
-        if bit {
-            let set = 1usize << shift;
-            result |= set;
+    swapped.sort();
+    swapped
+}
+
+/** Graphviz rendering of the circuit, with the swapped wires highlighted */
+struct Dot<'a, 'b>(&'b Circuit<'a>, &'b [Label<'a>]);
+
+impl Display for Dot<'_, '_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut gates: Vec<_> = self.0.gate_by_output_label.values().collect();
+        gates.sort_by_key(|gate| gate.output);
+
+        f.write_str("digraph circuit {\n    rankdir=LR;\n")?;
+        for gate in gates {
+            let output = gate.output;
+            f.write_fmt(format_args!("    \"{output} gate\" [shape=box, label=\"{:?}\"];\n", gate.operation))?;
+            f.write_fmt(format_args!("    \"{}\" -> \"{output} gate\";\n", gate.input_1))?;
+            f.write_fmt(format_args!("    \"{}\" -> \"{output} gate\";\n", gate.input_2))?;
+            f.write_fmt(format_args!("    \"{output} gate\" -> \"{output}\";\n"))?;
+        }
+        for label in self.1 {
+            f.write_fmt(format_args!("    \"{label}\" [style=filled, fillcolor=red];\n"))?;
         }
-        shift += 1;
-        key = format!("z{shift:>02}");
+        f.write_str("}\n")
     }
+}
+
+/** Outputs of the swapped gates, answered as comma separated list */
+struct SwappedWires<'a>(Vec<Label<'a>>);
 
-    result
+impl Display for SwappedWires<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0.iter().map(|label| label.to_string()).collect::<Vec<_>>().join(","))
+    }
 }
 
-fn calculate<'a, 'input>(gates: &'a FxHashMap<Label<'input>, Gate<'input>>, inputs: &'a FxHashMap<Label<'input>, Input<'input>>, cursor: &'a Gate<'input>) -> bool {
-    let input_by_label = |label: &Label<'input>| if let Some(input) = inputs.get(label) {
-        input.value
-    } else {
-        calculate(gates, inputs, gates.get(label).unwrap())
+fn part2<'a>(circuit: &Circuit<'a>) -> SwappedWires<'a> {
+    SwappedWires(swapped_outputs(circuit))
+}
+
+/** Writes the circuit as DOT if a file is configured, highlighting the swapped wires */
+fn write_requested_dot(circuit: &Circuit, swapped: Option<&SwappedWires>, out: &mut impl UIWrite) -> Result<(), String> {
+    let Some(path) = optional_parameter::<PathBuf>("dot_file") else {
+        return Ok(());
     };
 
-    let input_1 = input_by_label(&cursor.input_1);
-    let input_2 = input_by_label(&cursor.input_2);
+    let swapped = swapped.map_or_else(|| swapped_outputs(circuit), |swapped| swapped.0.clone());
+    write(&path, Dot(circuit, &swapped).to_string())
+        .map_err(|e| format!("Cannot write circuit to {}: {e}", path.display()))?;
+    out.info(format_args!("Wrote the circuit to {}", path.display()));
+    Ok(())
+}
+
+exporting_day!(parse, part1, part2, write_requested_dot);
+
+#[cfg(test)]
+mod test {
+    use crate::day::day24::{parse_wiring, swapped_outputs, Circuit, CircuitError, Label};
+
+    /** Three bit ripple-carry adder, all inputs zero */
+    const ADDER: &str = "\
+x00: 0
+x01: 0
+x02: 0
+y00: 0
+y01: 0
+y02: 0
+
+x00 XOR y00 -> z00
+x00 AND y00 -> c00
+x01 XOR y01 -> h01
+h01 XOR c00 -> z01
+x01 AND y01 -> a01
+h01 AND c00 -> b01
+a01 OR b01 -> c01
+x02 XOR y02 -> h02
+h02 XOR c01 -> z02
+x02 AND y02 -> a02
+h02 AND c01 -> b02
+a02 OR b02 -> z03
+";
 
-    match cursor.operation {
-        Operation::AND => { input_1 & input_2 },
-        Operation::OR => { input_1 | input_2 },
-        Operation::XOR => { input_1 ^ input_2 },
+    fn circuit(wiring: &str) -> Result<Circuit<'_>, CircuitError<'_>> {
+        let (_, (inputs, gates)) = parse_wiring(wiring.as_bytes()).unwrap();
+        Circuit::new(inputs, gates)
+    }
+
+    fn labels<'a>(names: &[&'a str]) -> Vec<Label<'a>> {
+        names.iter().map(|name| Label(name.as_bytes())).collect()
+    }
+
+    #[test]
+    fn adder_adds() {
+        let adder = circuit(ADDER).unwrap();
+        for x in 0..8 {
+            for y in 0..8 {
+                assert_eq!(x + y, adder.evaluate(x, y), "{x} + {y}");
+            }
+        }
+    }
+
+    #[test]
+    fn correct_adder_has_no_swaps() {
+        assert!(swapped_outputs(&circuit(ADDER).unwrap()).is_empty());
+    }
+
+    #[test]
+    fn finds_swapped_outputs() {
+        let swapped = ADDER
+            .replace("x01 XOR y01 -> h01", "x01 XOR y01 -> a01")
+            .replace("x01 AND y01 -> a01", "x01 AND y01 -> h01")
+            .replace("h01 XOR c00 -> z01", "h01 XOR c00 -> c01")
+            .replace("a01 OR b01 -> c01", "a01 OR b01 -> z01");
+
+        assert_eq!(labels(&["a01", "c01", "h01", "z01"]), swapped_outputs(&circuit(&swapped).unwrap()));
     }
-}
 
-parsed_day!(nom_parsed_bytes(parse), solve);
\ No newline at end of file
+    #[test]
+    fn reports_cycle() {
+        let looped = "x00: 1\ny00: 0\n\nx00 AND dd -> cc\ncc OR y00 -> dd\ncc XOR x00 -> z00\n";
+        let Err(CircuitError::Cycle(mut wires)) = circuit(looped) else {
+            panic!("cycle not detected");
+        };
+
+        wires.sort();
+        assert_eq!(labels(&["cc", "dd"]), wires);
+    }
+
+    #[test]
+    fn reports_undriven_wire() {
+        let dangling = "x00: 1\ny00: 0\n\nx00 AND qq -> z00\n";
+
+        assert!(matches!(
+            circuit(dangling),
+            Err(CircuitError::Undriven { wire: Label(b"qq"), gate: Label(b"z00") })
+        ));
+    }
+}
\ No newline at end of file
@@ -1,3 +1,106 @@
-use crate::unimplemented_day;
+use crate::collections::{Index2D, Vec2D};
+use crate::day::{parse_graphical_input, SimpleError};
+use crate::*;
 
-unimplemented_day!();
\ No newline at end of file
+const HEIGHT: usize = 7;
+const WIDTH: usize = 5;
+/** Schematics are separated by a blank line */
+const BLOCK_HEIGHT: usize = HEIGHT + 1;
+
+/**
+Column heights are stored in 4 bits per column. Lock and key fit if no column adds up to more
+than 5, so adding 2 to every column sets its top bit exactly where they overlap.
+*/
+const OVERLAP_BIAS: u32 = 0x22222;
+const OVERLAP_MASK: u32 = 0x88888;
+
+#[derive(Debug, Default)]
+struct Schematics {
+    locks: Vec<u32>,
+    keys: Vec<u32>,
+}
+
+fn encode_heights(grid: &Vec2D<bool>, top: usize) -> u32 {
+    (0..WIDTH).map(|column| {
+        let height = (top + 1..top + HEIGHT - 1)
+            .filter(|row| grid[Index2D { row: *row, column }])
+            .count();
+        (height as u32) << (4 * column)
+    }).sum()
+}
+
+fn parse(input: &[u8]) -> Result<Schematics, SimpleError> {
+    let mut filled = Vec::new();
+    parse_graphical_input(input, |byte, index| {
+        if byte == b'#' {
+            filled.push(index);
+        }
+    });
+    if let Some(outside) = filled.iter().find(|index| index.column >= WIDTH) {
+        return Err(SimpleError(format!("schematic wider than {WIDTH} at {outside:?}")));
+    }
+
+    // every schematic has a full row, so trailing blank lines end up past the last block
+    let blocks = filled.last().map_or(0, |last| last.row / BLOCK_HEIGHT + 1);
+    let mut grid = Vec2D::filled(false, blocks * BLOCK_HEIGHT, WIDTH);
+    for index in filled {
+        grid[index] = true;
+    }
+
+    let mut schematics = Schematics::default();
+    for block in 0..blocks {
+        let top = block * BLOCK_HEIGHT;
+        let full_row = |row: usize| (0..WIDTH).all(|column| grid[Index2D { row, column }]);
+
+        if full_row(top) {
+            schematics.locks.push(encode_heights(&grid, top));
+        } else if full_row(top + HEIGHT - 1) {
+            schematics.keys.push(encode_heights(&grid, top));
+        } else {
+            return Err(SimpleError(format!("schematic in rows {top}..{} is neither lock nor key", top + HEIGHT)));
+        }
+    }
+
+    Ok(schematics)
+}
+
+fn fits(lock: u32, key: u32) -> bool {
+    (lock + key + OVERLAP_BIAS) & OVERLAP_MASK == 0
+}
+
+fn count_fitting_pairs(schematics: Schematics) -> usize {
+    schematics.locks.iter()
+        .map(|lock| schematics.keys.iter().filter(|key| fits(*lock, **key)).count())
+        .sum()
+}
+
+parsed_day!(parse, count_fitting_pairs);
+
+#[cfg(test)]
+mod test {
+    use crate::day::day25::{count_fitting_pairs, parse};
+
+    const EXAMPLE: &[u8] = b"#####\n.####\n.####\n.####\n.#.#.\n.#...\n.....\n\n\
+#####\n##.##\n.#.##\n...##\n...#.\n...#.\n.....\n\n\
+.....\n#....\n#....\n#...#\n#.#.#\n#.###\n#####\n\n\
+.....\n.....\n#.#..\n###..\n###.#\n###.#\n#####\n\n\
+.....\n.....\n.....\n#....\n#.#..\n#.#.#\n#####\n";
+
+    #[test]
+    fn example() {
+        let schematics = parse(EXAMPLE).unwrap();
+        assert_eq!((2, 3), (schematics.locks.len(), schematics.keys.len()));
+        assert_eq!(3, count_fitting_pairs(schematics));
+    }
+
+    #[test]
+    fn accepts_trailing_blank_lines() {
+        let input = [EXAMPLE, b"\n\n\n"].concat();
+        assert_eq!(3, count_fitting_pairs(parse(&input).unwrap()));
+    }
+
+    #[test]
+    fn rejects_schematic_neither_lock_nor_key() {
+        assert!(parse(b"#####\n.....\n.....\n.....\n.....\n.....\n.....\n\n.#...\n.....\n.....\n.....\n.....\n.....\n.....\n").is_err());
+    }
+}
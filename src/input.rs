@@ -0,0 +1,230 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::fs::read;
+use std::io::{stdin, ErrorKind, Read};
+use std::path::{Path, PathBuf};
+
+/** Directory entry standing for standard input */
+pub const STDIN_MARKER: &str = "-";
+
+#[derive(Debug)]
+pub enum InputError {
+    /// none of the candidate locations exists
+    NotFound(Vec<PathBuf>),
+    PermissionDenied(PathBuf),
+    Empty(PathBuf),
+    Unreadable(PathBuf, std::io::Error),
+}
+
+impl Display for InputError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InputError::NotFound(paths) => {
+                f.write_str("input not found at ")?;
+                for (n, path) in paths.iter().enumerate() {
+                    if n > 0 {
+                        f.write_str(", ")?;
+                    }
+                    f.write_fmt(format_args!("{}", path.display()))?;
+                }
+                Ok(())
+            }
+            InputError::PermissionDenied(path) => f.write_fmt(format_args!("permission denied reading input at {}", path.display())),
+            InputError::Empty(path) => f.write_fmt(format_args!("input at {} is empty", path.display())),
+            InputError::Unreadable(path, e) => f.write_fmt(format_args!("cannot read input at {}: {e}", path.display())),
+        }
+    }
+}
+
+impl Error for InputError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            InputError::Unreadable(_, e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum InputSource {
+    Stdin,
+    File(PathBuf),
+}
+
+impl Display for InputSource {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InputSource::Stdin => f.write_str("<stdin>"),
+            InputSource::File(path) => f.write_fmt(format_args!("{}", path.display())),
+        }
+    }
+}
+
+impl InputSource {
+    pub fn read(&self) -> Result<Vec<u8>, InputError> {
+        let (path, contents) = match self {
+            InputSource::Stdin => {
+                let mut buffer = Vec::new();
+                (PathBuf::from(STDIN_MARKER), stdin().read_to_end(&mut buffer).map(|_| buffer))
+            }
+            InputSource::File(path) => (path.clone(), read(path)),
+        };
+
+        match contents {
+            Ok(contents) if contents.iter().all(u8::is_ascii_whitespace) => Err(InputError::Empty(path)),
+            Ok(contents) => Ok(contents),
+            Err(e) if e.kind() == ErrorKind::NotFound => Err(InputError::NotFound(vec![path])),
+            Err(e) if e.kind() == ErrorKind::PermissionDenied => Err(InputError::PermissionDenied(path)),
+            Err(e) => Err(InputError::Unreadable(path, e)),
+        }
+    }
+}
+
+/**
+Expands an input file template. Supported placeholders are `{dir}` for the input directory,
+and `{day}` for the day number, optionally zero padded (`{day:02}`).
+*/
+pub fn expand_template(template: &str, directory: &Path, day: u8) -> Result<PathBuf, String> {
+    let mut expanded = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(start) = rest.find('{') {
+        expanded.push_str(&rest[..start]);
+        let Some(length) = rest[start..].find('}') else {
+            return Err(format!("unterminated placeholder in {template}"));
+        };
+        let placeholder = &rest[start + 1..start + length];
+        match placeholder.split_once(':') {
+            None if placeholder == "dir" => expanded.push_str(&directory.to_string_lossy()),
+            None if placeholder == "day" => expanded.push_str(&day.to_string()),
+            Some(("day", width)) if width.starts_with('0') => {
+                let width = width.parse::<usize>().map_err(|_| format!("invalid padding in {{{placeholder}}}"))?;
+                expanded.push_str(&format!("{day:0width$}"));
+            }
+            _ => return Err(format!("unknown placeholder {{{placeholder}}} in {template}")),
+        }
+        rest = &rest[start + length + 1..];
+    }
+    expanded.push_str(rest);
+
+    Ok(PathBuf::from(expanded))
+}
+
+pub fn parse_input_template(template: &str) -> Result<String, String> {
+    expand_template(template, Path::new("."), 1).map(|_| template.to_string())
+}
+
+/**
+Finds the first directory in the lookup chain holding the input for the day. Only missing files
+fall through to the next directory; other failures to look a candidate up are reported.
+*/
+pub fn resolve_input(template: &str, directories: &[PathBuf], day: u8) -> Result<InputSource, InputError> {
+    let mut candidates = Vec::new();
+    for directory in directories {
+        if directory.as_os_str() == STDIN_MARKER {
+            return Ok(InputSource::Stdin);
+        }
+
+        let candidate = expand_template(template, directory, day)
+            .expect("Templates are validated on startup");
+        match candidate.try_exists() {
+            Ok(true) => return Ok(InputSource::File(candidate)),
+            Ok(false) => candidates.push(candidate),
+            Err(e) if e.kind() == ErrorKind::PermissionDenied => return Err(InputError::PermissionDenied(candidate)),
+            Err(e) => return Err(InputError::Unreadable(candidate, e)),
+        }
+    }
+
+    Err(InputError::NotFound(candidates))
+}
+
+#[cfg(test)]
+mod test {
+    use std::env::temp_dir;
+    use std::fs::{create_dir_all, remove_dir_all, write};
+    use std::path::{Path, PathBuf};
+    use crate::input::{expand_template, resolve_input, InputError, InputSource};
+
+    /** Fresh scratch directory, distinct per test and test run */
+    fn scratch(name: &str) -> PathBuf {
+        let directory = temp_dir().join(format!("aoc2024-input-{name}-{}", std::process::id()));
+        _ = remove_dir_all(&directory);
+        create_dir_all(&directory).unwrap();
+        directory
+    }
+
+    #[test]
+    fn pads_day_as_requested() {
+        let directory = Path::new("inputs");
+
+        assert_eq!(PathBuf::from("inputs/07"), expand_template("{dir}/{day:02}", directory, 7).unwrap());
+        assert_eq!(PathBuf::from("inputs/7.txt"), expand_template("{dir}/{day}.txt", directory, 7).unwrap());
+        assert_eq!(PathBuf::from("day007"), expand_template("day{day:03}", directory, 7).unwrap());
+        assert_eq!(PathBuf::from("inputs/12"), expand_template("{dir}/{day:02}", directory, 12).unwrap());
+    }
+
+    #[test]
+    fn rejects_unknown_placeholders() {
+        let directory = Path::new(".");
+
+        assert!(expand_template("{dir}/{month}", directory, 1).is_err());
+        assert!(expand_template("{day:2}", directory, 1).is_err());
+        assert!(expand_template("{day:0x}", directory, 1).is_err());
+        assert!(expand_template("{dir:02}", directory, 1).is_err());
+    }
+
+    #[test]
+    fn rejects_unterminated_placeholder() {
+        let error = expand_template("{dir}/{day", Path::new("."), 1).unwrap_err();
+
+        assert_eq!("unterminated placeholder in {dir}/{day", error);
+    }
+
+    #[test]
+    fn falls_back_to_later_directories() {
+        let first = scratch("first");
+        let second = scratch("second");
+        write(second.join("03"), "second").unwrap();
+        let directories = [first.clone(), second.clone()];
+
+        assert_eq!(InputSource::File(second.join("03")), resolve_input("{dir}/{day:02}", &directories, 3).unwrap());
+
+        write(first.join("03"), "first").unwrap();
+        assert_eq!(InputSource::File(first.join("03")), resolve_input("{dir}/{day:02}", &directories, 3).unwrap());
+
+        let Err(InputError::NotFound(candidates)) = resolve_input("{dir}/{day:02}", &directories, 4) else {
+            panic!("input of day 4 should not be found");
+        };
+        assert_eq!(vec![first.join("04"), second.join("04")], candidates);
+
+        _ = remove_dir_all(first);
+        _ = remove_dir_all(second);
+    }
+
+    #[test]
+    fn stdin_marker_ends_the_lookup() {
+        let directory = scratch("stdin");
+        write(directory.join("05"), "file").unwrap();
+
+        let missing = vec![directory.join("missing"), PathBuf::from("-"), directory.clone()];
+        assert_eq!(InputSource::Stdin, resolve_input("{dir}/{day:02}", &missing, 5).unwrap());
+        let present = vec![directory.clone(), PathBuf::from("-")];
+        assert_eq!(InputSource::File(directory.join("05")), resolve_input("{dir}/{day:02}", &present, 5).unwrap());
+
+        _ = remove_dir_all(directory);
+    }
+
+    #[test]
+    fn lookup_failures_other_than_missing_files_are_reported() {
+        let directory = scratch("not-a-directory");
+        let file = directory.join("file");
+        write(&file, "not a directory").unwrap();
+
+        let Err(InputError::Unreadable(path, _)) = resolve_input("{dir}/{day:02}", &[file.clone(), directory.clone()], 6) else {
+            panic!("a file used as directory should be reported");
+        };
+        assert_eq!(file.join("06"), path);
+
+        _ = remove_dir_all(directory);
+    }
+}
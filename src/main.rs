@@ -11,12 +11,24 @@ mod timed;
 
 mod parse_helpers;
 
-use std::fs::read;
+mod verify;
+
+mod input;
+
+mod parameters;
+
+use std::fs::read_to_string;
 use clap::Parser;
 use std::path::PathBuf;
 use std::process::ExitCode;
-use crate::ui::{UIMode};
+use crate::day::{declared_parameters, Answers, Part};
+use crate::input::{parse_input_template, resolve_input, InputError, STDIN_MARKER};
+use crate::parameters::{apply_assignments, load_parameter_overrides, parse_parameter_assignment, validate_overrides, ParameterOverrides, Parameters};
+use crate::ui::{OutputFormat, UIMode};
+use crate::verify::parse_expected_answers;
 
+/** Suffix distinguishing the files of the published examples from those of the real puzzles */
+const EXAMPLE_SUFFIX: &str = ".example";
 
 #[derive(Parser, Debug)]
 #[command(version, about, long_about = None)]
@@ -26,16 +38,84 @@ struct Configuration {
     ui_mode: UIMode,
     #[arg(long)]
     verbose: bool,
-    /// path to read input from
+    /// Result format of the optimized and bench runners
+    #[arg(long, default_value = "text", value_enum)]
+    output_format: OutputFormat,
+    /// unmeasured runs per day before benchmarking
+    #[arg(long, value_name = "RUNS", default_value_t = 3)]
+    warmup: u32,
+    /// measured runs per day when benchmarking
+    #[arg(long, value_name = "RUNS", default_value_t = 10, value_parser = clap::value_parser ! (u32).range(1..))]
+    iterations: u32,
+    /// store the benchmark results as baseline for later comparison (bench mode only)
+    #[arg(long, value_name = "FILE")]
+    save_baseline: Option<PathBuf>,
+    /// compare the benchmark results against a saved baseline (bench mode only)
+    #[arg(long, value_name = "FILE")]
+    baseline: Option<PathBuf>,
+    /// slowdown of a day's median against the baseline (in percent) reported as regression
+    #[arg(long, value_name = "PERCENT", default_value_t = 10.0)]
+    regression_threshold: f64,
+    /// compare results against the expected answers stored as NN.answer in the input directories
+    #[arg(long)]
+    verify: bool,
+    /// directories to look up inputs in, in order. "-" reads the input of a single day from stdin
     #[arg(long, value_name = "DIRECTORY", default_value = "./inputfiles")]
-    input_path: PathBuf,
+    input_path: Vec<PathBuf>,
+    /// input file location, with placeholders {dir} and {day} (padded e.g. as {day:02})
+    #[arg(long, value_name = "TEMPLATE", default_value = "{dir}/{day:02}", value_parser = parse_input_template)]
+    input_template: String,
+    /// run on the published examples (inputs NN.example, answers NN.example.answer), with example puzzle parameters
+    #[arg(long)]
+    example: bool,
+    /// file of puzzle parameter overrides, one "day.name = value" per line (e.g. "14.width = 11")
+    #[arg(long, value_name = "FILE")]
+    parameters: Option<PathBuf>,
+    /// puzzle parameter override, taking precedence over the parameters file (e.g. "14.frames_dir=frames")
+    #[arg(long = "parameter", value_name = "DAY.NAME=VALUE", value_parser = parse_parameter_assignment)]
+    parameter_assignments: Vec<(u8, String, String)>,
+    #[arg(skip)]
+    parameter_overrides: ParameterOverrides,
+    /// parts to run. Selecting only part 2 still runs part 1 unreported on the days whose part 2 builds on it
+    #[arg(long, default_value = "both", value_enum)]
+    part: Part,
     /// days to run (don't specify to run all available)
     #[arg(value_parser = clap::value_parser ! (u8).range(1..=25))]
     run_days: Vec<u8>,
 }
 
 impl Configuration {
-    fn run(self) -> ExitCode {
+    fn run(mut self) -> ExitCode {
+        if let Some(path) = &self.parameters {
+            match load_parameter_overrides(path) {
+                Ok(overrides) => self.parameter_overrides = overrides,
+                Err(error) => {
+                    eprintln!("{error}");
+                    return ExitCode::FAILURE;
+                }
+            }
+        }
+        apply_assignments(&mut self.parameter_overrides, self.parameter_assignments.drain(..));
+        if let Err(error) = validate_overrides(&self.parameter_overrides, declared_parameters) {
+            eprintln!("{error}");
+            return ExitCode::FAILURE;
+        }
+        if self.example {
+            self.input_template.push_str(EXAMPLE_SUFFIX);
+        }
+
+        let reads_stdin = self.input_path.iter().any(|path| path.as_os_str() == STDIN_MARKER);
+        if reads_stdin && self.active_days().len() != 1 {
+            eprintln!("reading input from stdin requires selecting exactly one day");
+            return ExitCode::FAILURE;
+        }
+
+        let uses_baseline = self.baseline.is_some() || self.save_baseline.is_some();
+        if uses_baseline && self.ui_mode != UIMode::Bench {
+            eprintln!("--baseline and --save-baseline require --ui-mode bench");
+            return ExitCode::FAILURE;
+        }
+
         self.ui_mode.run(self)
     }
 
@@ -49,15 +129,32 @@ impl Configuration {
         }
     }
 
-    fn load_input(&self, day: u8) -> Vec<u8> {
-        let mut path = self.input_path.clone();
-        path.push(format!("{:02}", day));
+    fn load_input(&self, day: u8) -> Result<Vec<u8>, InputError> {
+        let source = resolve_input(&self.input_template, &self.input_path, day)?;
+        if self.verbose {
+            eprintln!("day {day}: reading input from {source}");
+        }
 
-        if let Ok(loaded_contents) = read(&path) {
-            loaded_contents
-        } else {
-            Vec::new()
+        source.read()
+    }
+
+    fn parameters(&self, day: u8) -> Parameters {
+        let overrides = self.parameter_overrides.get(&day).cloned().unwrap_or_default();
+        Parameters::new(self.example, overrides)
+    }
+
+    fn load_expected_answers(&self, day: u8) -> Answers {
+        let suffix = if self.example { EXAMPLE_SUFFIX } else { "" };
+        for directory in self.input_path.iter() {
+            let mut path = directory.clone();
+            path.push(format!("{:02}{suffix}.answer", day));
+
+            if let Ok(loaded_contents) = read_to_string(&path) {
+                return parse_expected_answers(&loaded_contents).only(self.part);
+            }
         }
+
+        Answers::default()
     }
 }
 
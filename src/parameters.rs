@@ -0,0 +1,253 @@
+use std::cell::RefCell;
+use std::fs::read_to_string;
+use std::path::Path;
+use std::str::FromStr;
+use fxhash::FxHashMap;
+
+/**
+Puzzle parameters of a single day. Days hardcoding properties of the real input (grid sizes,
+thresholds, iteration counts) look them up by name, supplying the value for the real input and
+the one for the published example. Values given in a parameters file take precedence over both.
+*/
+#[derive(Debug, Clone, Default)]
+pub struct Parameters {
+    example: bool,
+    overrides: FxHashMap<String, String>,
+}
+
+impl Parameters {
+    pub fn new(example: bool, overrides: FxHashMap<String, String>) -> Self {
+        Self { example, overrides }
+    }
+
+    pub fn get<T: FromStr>(&self, name: &str, real: T, example: T) -> T {
+        match self.get_optional(name) {
+            Some(value) => value,
+            None if self.example => example,
+            None => real,
+        }
+    }
+
+    /** Parameters without a default, only present when given explicitly */
+    pub fn get_optional<T: FromStr>(&self, name: &str) -> Option<T> {
+        self.overrides.get(name).map(|value| {
+            value.parse().unwrap_or_else(|_| panic!("Invalid value '{value}' for parameter {name}"))
+        })
+    }
+}
+
+/** A parameter a day looks up, with the type its value has to parse as */
+#[derive(Debug, Clone, Copy)]
+pub struct ParameterSpec {
+    pub name: &'static str,
+    accepts: fn(&str) -> bool,
+}
+
+impl ParameterSpec {
+    pub const fn new<T: FromStr>(name: &'static str) -> Self {
+        Self { name, accepts: parses_as::<T> }
+    }
+}
+
+fn parses_as<T: FromStr>(value: &str) -> bool {
+    value.parse::<T>().is_ok()
+}
+
+/** Parameter overrides per day, as read from a parameters file */
+pub type ParameterOverrides = FxHashMap<u8, FxHashMap<String, String>>;
+
+/** Parses a single `day.name = value` assignment, e.g. `14.width = 11` */
+pub fn parse_parameter_assignment(assignment: &str) -> Result<(u8, String, String), String> {
+    let malformed = || format!("expected day.name=value, got {assignment}");
+    let (key, value) = assignment.split_once('=').ok_or_else(malformed)?;
+    let (day, name) = key.trim().split_once('.').ok_or_else(malformed)?;
+    let day = day.parse::<u8>().map_err(|_| malformed())?;
+    if !(1..=25).contains(&day) {
+        return Err(format!("day {day} is not in 1..=25, in {assignment}"));
+    }
+
+    Ok((day, name.to_string(), value.trim().to_string()))
+}
+
+/** Adds the assignments to the overrides, replacing the values given for the same parameter before */
+pub fn apply_assignments(overrides: &mut ParameterOverrides, assignments: impl IntoIterator<Item = (u8, String, String)>) {
+    for (day, name, value) in assignments {
+        overrides.entry(day).or_default().insert(name, value);
+    }
+}
+
+/**
+Checks the overrides against the parameters the days declare, so that misspelled names and
+unparsable values are reported on startup instead of being ignored or failing the day mid-run.
+*/
+pub fn validate_overrides(overrides: &ParameterOverrides, declared: impl Fn(u8) -> &'static [ParameterSpec]) -> Result<(), String> {
+    let mut assignments: Vec<_> = overrides.iter()
+        .flat_map(|(day, values)| values.iter().map(move |(name, value)| (*day, name, value)))
+        .collect();
+    assignments.sort();
+
+    for (day, name, value) in assignments {
+        let specs = declared(day);
+        let Some(spec) = specs.iter().find(|spec| spec.name == name) else {
+            let known: Vec<_> = specs.iter().map(|spec| spec.name).collect();
+            return Err(match known.as_slice() {
+                [] => format!("unknown parameter {day}.{name}, day {day} has no parameters"),
+                known => format!("unknown parameter {day}.{name}, day {day} has {}", known.join(", ")),
+            });
+        };
+        if !(spec.accepts)(value) {
+            return Err(format!("invalid value '{value}' for parameter {day}.{name}"));
+        }
+    }
+
+    Ok(())
+}
+
+/**
+Parameters files hold one assignment per line. Empty lines and lines starting with `#` are ignored.
+*/
+pub fn load_parameter_overrides(path: &Path) -> Result<ParameterOverrides, String> {
+    let contents = read_to_string(path)
+        .map_err(|e| format!("cannot read parameters {}: {e}", path.display()))?;
+    let mut overrides = ParameterOverrides::default();
+
+    for (line_nr, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let assignment = parse_parameter_assignment(line)
+            .map_err(|e| format!("malformed parameter in {}, line {}: {e}", path.display(), line_nr + 1))?;
+        apply_assignments(&mut overrides, [assignment]);
+    }
+
+    Ok(overrides)
+}
+
+thread_local! {
+    static ACTIVE: RefCell<Parameters> = RefCell::new(Parameters::default());
+}
+
+struct RestoreOnDrop(Option<Parameters>);
+
+impl Drop for RestoreOnDrop {
+    fn drop(&mut self) {
+        if let Some(previous) = self.0.take() {
+            ACTIVE.set(previous);
+        }
+    }
+}
+
+/**
+Makes the parameters available to `parameter` for the duration of `f`. Parameters are only
+visible on the calling thread, so look them up before handing work to other threads.
+*/
+pub fn with_parameters<R>(parameters: Parameters, f: impl FnOnce() -> R) -> R {
+    let _restore = RestoreOnDrop(Some(ACTIVE.replace(parameters)));
+    f()
+}
+
+/** Looks up a parameter of the running day */
+pub fn parameter<T: FromStr>(name: &str, real: T, example: T) -> T {
+    ACTIVE.with_borrow(|parameters| parameters.get(name, real, example))
+}
+
+/** Looks up a parameter of the running day that has no default */
+pub fn optional_parameter<T: FromStr>(name: &str) -> Option<T> {
+    ACTIVE.with_borrow(|parameters| parameters.get_optional(name))
+}
+
+#[cfg(test)]
+mod test {
+    use std::env::temp_dir;
+    use std::fs::{remove_file, write};
+    use std::path::PathBuf;
+    use crate::parameters::{apply_assignments, load_parameter_overrides, parse_parameter_assignment, validate_overrides, ParameterOverrides, ParameterSpec};
+
+    const DAY14: &[ParameterSpec] = &[ParameterSpec::new::<usize>("width"), ParameterSpec::new::<PathBuf>("frames_dir")];
+
+    fn declared(day: u8) -> &'static [ParameterSpec] {
+        if day == 14 { DAY14 } else { &[] }
+    }
+
+    fn load(name: &str, contents: &str) -> Result<ParameterOverrides, String> {
+        let path = temp_dir().join(format!("aoc2024-parameters-{name}-{}", std::process::id()));
+        write(&path, contents).unwrap();
+        let loaded = load_parameter_overrides(&path);
+        _ = remove_file(path);
+        loaded
+    }
+
+    fn overrides(assignments: &[(u8, &str, &str)]) -> ParameterOverrides {
+        let mut overrides = ParameterOverrides::default();
+        apply_assignments(&mut overrides, assignments.iter().map(|(day, name, value)| (*day, name.to_string(), value.to_string())));
+        overrides
+    }
+
+    #[test]
+    fn parses_assignment() {
+        assert_eq!((14, "width".to_string(), "11".to_string()), parse_parameter_assignment("14.width = 11").unwrap());
+        assert_eq!((1, "dir".to_string(), "a=b".to_string()), parse_parameter_assignment("1.dir=a=b").unwrap());
+    }
+
+    #[test]
+    fn rejects_malformed_assignment() {
+        assert!(parse_parameter_assignment("14.width").is_err());
+        assert!(parse_parameter_assignment("width=11").is_err());
+        assert!(parse_parameter_assignment("x.width=11").is_err());
+    }
+
+    #[test]
+    fn rejects_days_out_of_range() {
+        assert!(parse_parameter_assignment("0.width=3").is_err());
+        assert!(parse_parameter_assignment("26.width=3").is_err());
+        assert!(parse_parameter_assignment("99.width=3").is_err());
+        assert!(parse_parameter_assignment("25.width=3").is_ok());
+    }
+
+    #[test]
+    fn file_skips_comments_and_blank_lines() {
+        let loaded = load("comments", "# sizes of the example\n\n14.width = 11\n   \n  # indented comment\n14.height=7\n18.target = 6\n").unwrap();
+
+        assert_eq!(overrides(&[(14, "width", "11"), (14, "height", "7"), (18, "target", "6")]), loaded);
+    }
+
+    #[test]
+    fn file_reports_the_bad_line() {
+        let error = load("bad-line", "14.width = 11\n\nwidth = 11\n").unwrap_err();
+
+        assert!(error.contains("line 3"), "{error}");
+        assert!(load("bad-day", "30.width = 11\n").is_err());
+    }
+
+    #[test]
+    fn later_assignments_take_precedence() {
+        let mut overrides = load("precedence", "14.width = 11\n14.height = 7\n").unwrap();
+        apply_assignments(&mut overrides, [(14, "width".to_string(), "101".to_string())]);
+
+        assert_eq!(Some(&"101".to_string()), overrides[&14].get("width"));
+        assert_eq!(Some(&"7".to_string()), overrides[&14].get("height"));
+    }
+
+    #[test]
+    fn accepts_declared_parameters() {
+        assert!(validate_overrides(&overrides(&[(14, "width", "11"), (14, "frames_dir", "frames")]), declared).is_ok());
+    }
+
+    #[test]
+    fn rejects_undeclared_names() {
+        let error = validate_overrides(&overrides(&[(14, "widht", "11")]), declared).unwrap_err();
+        assert_eq!("unknown parameter 14.widht, day 14 has width, frames_dir", error);
+
+        let error = validate_overrides(&overrides(&[(3, "width", "11")]), declared).unwrap_err();
+        assert_eq!("unknown parameter 3.width, day 3 has no parameters", error);
+    }
+
+    #[test]
+    fn rejects_unparsable_values() {
+        let error = validate_overrides(&overrides(&[(14, "width", "eleven")]), declared).unwrap_err();
+
+        assert_eq!("invalid value 'eleven' for parameter 14.width", error);
+    }
+}
@@ -5,10 +5,6 @@ use nom::combinator::{map, opt};
 use nom::IResult;
 use nom::sequence::tuple;
 
-pub fn infallible_parse<T>(mut p: impl FnMut(&[u8]) -> T) -> impl FnMut(&[u8]) -> Result<T, !> {
-    move |input|Ok(p(input))
-}
-
 pub fn parse_signed_nr_bytes<T: Mul<Output=T> + Add<Output=T> + From<u8> + Neg<Output=T> + Copy>(input: &[u8]) -> IResult<&[u8], T> {
     let (rest, (minus, mut value)) = tuple((opt(tag(b"-")), parse_unsigned_nr_bytes::<T>))(input)?;
 
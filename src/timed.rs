@@ -20,4 +20,112 @@ pub fn work(f: impl FnOnce()) {
     let (_, duration) = time_span(f);
     let nanos = duration.as_nanos();
     WORK.fetch_add(nanos as u64, Ordering::AcqRel);
-}
\ No newline at end of file
+}
+
+/** Summary of repeated timing samples */
+#[derive(Debug, Copy, Clone)]
+pub struct Statistics {
+    pub samples: usize,
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+    pub p95: Duration,
+    pub stddev: Duration,
+}
+
+impl Statistics {
+    pub fn from_samples(samples: &mut [Duration]) -> Option<Self> {
+        if samples.is_empty() {
+            return None;
+        }
+        samples.sort();
+
+        let n = samples.len();
+        let median = if n % 2 == 0 {
+            (samples[n / 2 - 1] + samples[n / 2]) / 2
+        } else {
+            samples[n / 2]
+        };
+        // nearest rank percentile
+        let p95 = samples[(n * 95).div_ceil(100) - 1];
+        let mean_nanos = samples.iter().map(|it| it.as_nanos() as f64).sum::<f64>() / n as f64;
+        let variance = samples.iter().map(|it| {
+            let delta = it.as_nanos() as f64 - mean_nanos;
+            delta * delta
+        }).sum::<f64>() / n as f64;
+
+        Some(Self {
+            samples: n,
+            min: samples[0],
+            median,
+            mean: Duration::from_nanos(mean_nanos as u64),
+            p95,
+            stddev: Duration::from_nanos(variance.sqrt() as u64),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+    use crate::timed::Statistics;
+
+    fn millis(values: impl IntoIterator<Item = u64>) -> Vec<Duration> {
+        values.into_iter().map(Duration::from_millis).collect()
+    }
+
+    fn statistics(values: impl IntoIterator<Item = u64>) -> Statistics {
+        Statistics::from_samples(&mut millis(values)).unwrap()
+    }
+
+    #[test]
+    fn median_of_odd_count() {
+        let statistics = statistics([5, 1, 3]);
+
+        assert_eq!(Duration::from_millis(3), statistics.median);
+        assert_eq!(Duration::from_millis(1), statistics.min);
+        assert_eq!(Duration::from_millis(3), statistics.mean);
+    }
+
+    #[test]
+    fn median_of_even_count_averages_the_middle() {
+        assert_eq!(Duration::from_millis(5), statistics([8, 2, 4, 6]).median);
+    }
+
+    #[test]
+    fn p95_is_the_nearest_rank() {
+        assert_eq!(Duration::from_millis(1), statistics(1..=1).p95);
+        assert_eq!(Duration::from_millis(19), statistics(1..=19).p95);
+        assert_eq!(Duration::from_millis(19), statistics(1..=20).p95);
+        assert_eq!(Duration::from_millis(20), statistics(1..=21).p95);
+    }
+
+    #[test]
+    fn constant_samples_have_no_deviation() {
+        let statistics = statistics([7; 10]);
+
+        assert_eq!(Duration::ZERO, statistics.stddev);
+        assert_eq!(Duration::from_millis(7), statistics.mean);
+    }
+
+    #[test]
+    fn stddev_of_spread_samples() {
+        assert_eq!(Duration::from_millis(2), statistics([2, 4, 4, 4, 5, 5, 7, 9]).stddev);
+    }
+
+    #[test]
+    fn no_statistics_without_samples() {
+        assert!(Statistics::from_samples(&mut []).is_none());
+    }
+
+    #[test]
+    fn single_sample() {
+        let statistics = statistics([4]);
+
+        assert_eq!(1, statistics.samples);
+        assert_eq!(Duration::from_millis(4), statistics.min);
+        assert_eq!(Duration::from_millis(4), statistics.median);
+        assert_eq!(Duration::from_millis(4), statistics.p95);
+        assert_eq!(Duration::ZERO, statistics.stddev);
+    }
+}
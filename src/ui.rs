@@ -1,6 +1,9 @@
 #[cfg(feature = "gtk_ui")]
 mod gtk;
 mod console;
+mod report;
+mod bench;
+mod baseline;
 
 use std::fmt::{Arguments};
 use std::io::Write;
@@ -14,6 +17,16 @@ pub enum UIMode {
     GTK,
     Console,
     Optimized,
+    /// repeated runs of every day, reporting timing statistics
+    Bench,
+}
+
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, ValueEnum)]
+pub enum OutputFormat {
+    Text,
+    /// one JSON object per line and day
+    Json,
+    Csv,
 }
 
 impl UIMode {
@@ -22,7 +35,8 @@ impl UIMode {
             #[cfg(feature = "gtk_ui")]
             UIMode::GTK => gtk::gtk_run(config),
             UIMode::Console => console::console_run(config),
-            UIMode::Optimized => console::optimized_run(config)
+            UIMode::Optimized => console::optimized_run(config),
+            UIMode::Bench => bench::bench_run(config),
         }
     }
 }
@@ -35,6 +49,8 @@ pub trait UIWrite : Write {
     fn info(&mut self, fmt: Arguments<'_>);
     fn critical(&mut self, fmt: Arguments<'_>);
     fn result(&mut self, fmt: Arguments<'_>);
+    /** Whether info is shown at all, so days can skip building reports nobody sees */
+    fn shows_info(&self) -> bool;
 }
 
 pub struct Verbose;
@@ -84,6 +100,10 @@ impl <T: Write> UIWrite for VerboseUI<T> {
         _ = self.write(b"\n");
         _ = self.flush();
     }
+
+    fn shows_info(&self) -> bool {
+        true
+    }
 }
 
 pub struct Terse;
@@ -128,4 +148,8 @@ impl <T: Write> UIWrite for TerseUI<T> {
         _ = self.write(b"\n");
         _ = self.flush()
     }
+
+    fn shows_info(&self) -> bool {
+        false
+    }
 }
\ No newline at end of file
@@ -0,0 +1,128 @@
+use std::fmt::{Display, Formatter};
+use std::fs::{read_to_string, write};
+use std::path::Path;
+use std::time::Duration;
+use fxhash::FxHashMap;
+use crate::ui::report::{BenchRecord, render_bench_csv};
+
+/**
+Phase compared against the baseline. Individual phases of fast days are too noisy to
+gate on, so regressions are judged on the overall handler time.
+*/
+const COMPARED_PHASE: &str = "total";
+
+/** Median timings per day, as stored by an earlier benchmark run */
+pub struct Baseline {
+    medians: FxHashMap<u8, Duration>,
+}
+
+/**
+Baselines are stored in the bench CSV format, so any `--output-format csv` bench run
+can serve as baseline as well.
+*/
+pub fn save_baseline(path: &Path, records: &[BenchRecord]) -> Result<(), String> {
+    write(path, render_bench_csv(records))
+        .map_err(|e| format!("cannot write baseline {}: {e}", path.display()))
+}
+
+pub fn load_baseline(path: &Path) -> Result<Baseline, String> {
+    let contents = read_to_string(path)
+        .map_err(|e| format!("cannot read baseline {}: {e}", path.display()))?;
+    let mut medians = FxHashMap::default();
+
+    for (line_nr, line) in contents.lines().enumerate().skip(1) {
+        let fields: Vec<_> = line.split(',').collect();
+        let malformed = || format!("malformed baseline {}, line {}: {line}", path.display(), line_nr + 1);
+        let [day, phase, _samples, _min, median, ..] = fields.as_slice() else {
+            return Err(malformed());
+        };
+        if *phase != COMPARED_PHASE {
+            continue;
+        }
+
+        let day = day.parse::<u8>().map_err(|_| malformed())?;
+        let median = median.parse::<u64>().map_err(|_| malformed())?;
+        medians.insert(day, Duration::from_nanos(median));
+    }
+
+    Ok(Baseline { medians })
+}
+
+pub struct Comparison {
+    day: u8,
+    baseline: Duration,
+    current: Duration,
+    threshold_percent: f64,
+}
+
+impl Comparison {
+    /** Relative change against the baseline, none if the baseline median is zero */
+    fn change_percent(&self) -> Option<f64> {
+        if self.baseline.is_zero() {
+            return None;
+        }
+        let baseline = self.baseline.as_nanos() as f64;
+        let current = self.current.as_nanos() as f64;
+        Some((current - baseline) / baseline * 100.0)
+    }
+
+    pub fn regressed(&self) -> bool {
+        self.change_percent().is_some_and(|change| change > self.threshold_percent)
+    }
+}
+
+impl Display for Comparison {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!("Day {:>2}: median {:?} vs. baseline {:?}", self.day, self.current, self.baseline))?;
+        match self.change_percent() {
+            Some(change) => f.write_fmt(format_args!(" ({change:+.1}%){}", if self.regressed() { " REGRESSED" } else { "" })),
+            None => f.write_str(" (not comparable)"),
+        }
+    }
+}
+
+impl Baseline {
+    /** Compares all days present in both the baseline and the current run */
+    pub fn compare(&self, records: &[BenchRecord], threshold_percent: f64) -> Vec<Comparison> {
+        records.iter()
+            .filter(|record| record.phase == COMPARED_PHASE)
+            .filter_map(|record| {
+                let baseline = *self.medians.get(&record.day)?;
+                Some(Comparison {
+                    day: record.day,
+                    baseline,
+                    current: record.statistics.median,
+                    threshold_percent,
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+    use crate::ui::baseline::Comparison;
+
+    fn comparison(baseline: u64, current: u64) -> Comparison {
+        Comparison {
+            day: 1,
+            baseline: Duration::from_nanos(baseline),
+            current: Duration::from_nanos(current),
+            threshold_percent: 10.0,
+        }
+    }
+
+    #[test]
+    fn regression_past_threshold() {
+        assert!(comparison(100, 111).regressed());
+        assert!(!comparison(100, 110).regressed());
+        assert_eq!("Day  1: median 111ns vs. baseline 100ns (+11.0%) REGRESSED", comparison(100, 111).to_string());
+    }
+
+    #[test]
+    fn zero_baseline_is_not_comparable() {
+        assert!(!comparison(0, 5).regressed());
+        assert_eq!("Day  1: median 5ns vs. baseline 0ns (not comparable)", comparison(0, 5).to_string());
+    }
+}
@@ -0,0 +1,139 @@
+use std::io::{sink, Sink};
+use std::process::ExitCode;
+use std::time::Duration;
+use crate::Configuration;
+use crate::day::{Day, DayResult, handlers};
+use crate::timed::{time_span, Statistics};
+use crate::ui::OutputFormat;
+use crate::ui::baseline::{load_baseline, save_baseline};
+use crate::ui::report::{BenchRecord, render_bench_csv, render_bench_json, render_bench_table};
+
+#[derive(Default)]
+struct Samples {
+    parse: Vec<Duration>,
+    part1: Vec<Duration>,
+    part2: Vec<Duration>,
+    combined: Vec<Duration>,
+    total: Vec<Duration>,
+}
+
+impl Samples {
+    fn record(&mut self, result: &DayResult, total: Duration) {
+        let timings = &result.timings;
+        let phases = [
+            (&mut self.parse, timings.parse),
+            (&mut self.part1, timings.part1),
+            (&mut self.part2, timings.part2),
+            (&mut self.combined, timings.combined),
+        ];
+        for (samples, timing) in phases {
+            if let Some(timing) = timing {
+                samples.push(timing);
+            }
+        }
+        self.total.push(total);
+    }
+
+    fn into_records(self, day: u8) -> impl Iterator<Item = BenchRecord> {
+        [
+            ("parse", self.parse),
+            ("part1", self.part1),
+            ("part2", self.part2),
+            ("combined", self.combined),
+            ("total", self.total),
+        ].into_iter().filter_map(move |(phase, mut samples)| {
+            Statistics::from_samples(&mut samples).map(|statistics| BenchRecord { day, phase, statistics })
+        })
+    }
+}
+
+fn bench_day(day: u8, handler: &Day<Sink>, input: &[u8], config: &Configuration) -> Result<Samples, String> {
+    let mut samples = Samples::default();
+    let parameters = config.parameters(day);
+    for iteration in 0..config.warmup + config.iterations {
+        let (result, total) = time_span(|| handler.execute(day, false, config.part, &parameters, input, &mut sink()));
+        if let Some(error) = result.error {
+            return Err(error);
+        }
+
+        if iteration >= config.warmup {
+            samples.record(&result, total);
+        }
+    }
+
+    Ok(samples)
+}
+
+pub fn bench_run(config: Configuration) -> ExitCode {
+    static HANDLERS: [Option<Day<Sink>>; 25] = handlers::<Sink>();
+    let mut records = Vec::new();
+    let mut failed = false;
+
+    let baseline = match config.baseline.as_deref().map(load_baseline) {
+        Some(Err(error)) => {
+            eprintln!("{error}");
+            return ExitCode::FAILURE;
+        }
+        Some(Ok(baseline)) => Some(baseline),
+        None => None,
+    };
+
+    for day in config.active_days() {
+        let day = *day;
+        let index = (day - 1) as usize;
+        if let Some(handler) = &HANDLERS[index] {
+            let input = match config.load_input(day) {
+                Ok(input) => input,
+                Err(error) => {
+                    eprintln!("day {day}: {error}");
+                    continue;
+                }
+            };
+            if config.verbose {
+                eprintln!("Day {day}: {} warm-up and {} measured iterations", config.warmup, config.iterations);
+            }
+
+            match bench_day(day, handler, &input, &config) {
+                Ok(samples) => records.extend(samples.into_records(day)),
+                Err(error) => {
+                    eprintln!("Day {day}: benchmark aborted: {error}");
+                    failed = true;
+                }
+            }
+        }
+    }
+
+    let rendered = match config.output_format {
+        OutputFormat::Text => render_bench_table(&records),
+        OutputFormat::Json => render_bench_json(&records),
+        OutputFormat::Csv => render_bench_csv(&records),
+    };
+    print!("{rendered}");
+
+    if let Some(path) = &config.save_baseline {
+        if let Err(error) = save_baseline(path, &records) {
+            eprintln!("{error}");
+            failed = true;
+        }
+    }
+
+    if let Some(baseline) = baseline {
+        let mut comparison_output = String::new();
+        for comparison in baseline.compare(&records, config.regression_threshold) {
+            failed |= comparison.regressed();
+            comparison_output += &format!("{comparison}\n");
+        }
+
+        if config.output_format == OutputFormat::Text {
+            print!("\nBaseline comparison:\n{comparison_output}");
+        } else {
+            eprint!("{comparison_output}");
+        }
+    }
+
+    if failed {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
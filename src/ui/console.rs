@@ -2,35 +2,54 @@ use std::io::{stdout, Stdout};
 use std::process::ExitCode;
 use std::time::{Duration, Instant};
 use crate::Configuration;
-use crate::day::{Day, handlers};
+use crate::day::{Day, DayResult, Part, handlers};
+use crate::parameters::Parameters;
 use crate::timed::{time_span, work_duration};
+use crate::ui::OutputFormat;
+use crate::ui::report::{DayRecord, render_csv, render_json};
+use crate::verify::DayVerification;
 use crate::worker::parallelize_ordered;
 
 
 pub fn console_run(config: Configuration) -> ExitCode {
     static HANDLERS: [Option<Day<Stdout>>; 25] = handlers::<Stdout>();
+    let mut failed = false;
     for day in config.active_days() {
         let index = (*day - 1) as usize;
         if let Some(solution) = &HANDLERS[index] {
-            let handler = if config.verbose {
-                solution.verbose
-            } else {
-                solution.terse
+            let input = match config.load_input(*day) {
+                Ok(input) => input,
+                Err(error) => {
+                    eprintln!("day {day}: {error}");
+                    continue;
+                }
             };
-            handler(&config.load_input(*day), &mut stdout());
+            let result = solution.execute(*day, config.verbose, config.part, &config.parameters(*day), &input, &mut stdout());
+            failed |= result.error.is_some();
+
+            if config.verify {
+                let verification = DayVerification::new(*day, config.load_expected_answers(*day), result.answers);
+                failed |= verification.failed();
+                println!("{verification}");
+            }
         }
     }
 
-    ExitCode::SUCCESS
+    if failed {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
 }
 
-fn execute_day_handler(day: u8, day_handler_function: fn(&[u8], &mut Vec<u8>), input: Vec<u8>) -> OptimizedOutput {
+fn execute_day_handler(day: u8, handler: &Day<Vec<u8>>, verbose: bool, part: Part, parameters: Parameters, input: Vec<u8>) -> OptimizedOutput {
     let mut output_buffer = Vec::new();
-    let (_, timing) = time_span(|| day_handler_function(&input, &mut output_buffer));
+    let (result, timing) = time_span(|| handler.execute(day, verbose, part, &parameters, &input, &mut output_buffer));
     OptimizedOutput {
         day,
         timing,
         output_buffer,
+        result,
     }
 }
 
@@ -38,38 +57,59 @@ struct OptimizedOutput {
     day: u8,
     timing: Duration,
     output_buffer: Vec<u8>,
+    result: DayResult,
 }
 
 pub fn optimized_run(config: Configuration) -> ExitCode {
     static HANDLERS: [Option<Day<Vec<u8>>>; 25] = handlers::<Vec<u8>>();
     let clock_start = Instant::now();
+    let verbose = config.verbose;
+    let part = config.part;
     let tasks = config.active_days().into_iter().filter_map(|day| {
         let day = *day;
         let index = (day - 1) as usize;
         if let Some(handler) = &HANDLERS[index] {
-            let handler = if config.verbose {
-                handler.verbose
-            } else {
-                handler.terse
-            };
-            let input = config.load_input(day);
-            Some(move || execute_day_handler(day, handler, input))
+            match config.load_input(day) {
+                Ok(input) => {
+                    let parameters = config.parameters(day);
+                    Some(move || execute_day_handler(day, handler, verbose, part, parameters, input))
+                }
+                Err(error) => {
+                    eprintln!("day {day}: {error}");
+                    None
+                }
+            }
         } else {
             None
         }
     });
     let mut overall_output = String::new();
     let mut day_eval_timings = String::new();
+    let mut verification_output = String::new();
+    let mut failed = false;
     let results = parallelize_ordered(tasks);
-    for OptimizedOutput {day, timing, output_buffer} in results {
-        overall_output += &String::from_utf8(output_buffer).expect("Valid utf8");
-        day_eval_timings += &format!("Day {day}: {timing:?}\n")
+    for OptimizedOutput {day, timing, output_buffer, result} in results.iter() {
+        overall_output += &String::from_utf8_lossy(output_buffer);
+        day_eval_timings += &format!("Day {day}: {timing:?}\n");
+        failed |= result.error.is_some();
+
+        if config.verify {
+            let verification = DayVerification::new(*day, config.load_expected_answers(*day), result.answers.clone());
+            failed |= verification.failed();
+            verification_output += &format!("{verification}\n");
+        }
     }
 
     let clock_duration = Instant::now() - clock_start;
     let total_duration = work_duration();
 
-    println!("Overall run complete.
+    let records: Vec<_> = results.iter().map(|OptimizedOutput { day, timing, result, .. }| {
+        DayRecord { day: *day, wall_time: *timing, result }
+    }).collect();
+
+    match config.output_format {
+        OutputFormat::Text => {
+            println!("Overall run complete.
 Wall time: {clock_duration:?}
 Sum Task CPU time: {total_duration:?}
 
@@ -79,6 +119,32 @@ Output:
 Day evaluation times:
 {day_eval_timings}");
 
-    ExitCode::SUCCESS
+            if config.verify {
+                println!("Verification:
+{verification_output}");
+            }
+        }
+        structured => {
+            // keep stdout machine readable - everything else goes to stderr
+            if config.verbose {
+                eprint!("{overall_output}");
+            }
+            if config.verify {
+                eprint!("{verification_output}");
+            }
+
+            if structured == OutputFormat::Json {
+                print!("{}", render_json(&records));
+            } else {
+                print!("{}", render_csv(&records));
+            }
+        }
+    }
+
+    if failed {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
 }
 
@@ -11,6 +11,7 @@ use std::sync::mpsc::{channel, Receiver, Sender, TryRecvError};
 use std::time::Duration;
 use crate::Configuration;
 use crate::day::{Day, handlers};
+use crate::verify::DayVerification;
 
 use crate::worker::run_on_worker;
 
@@ -54,7 +55,12 @@ fn build_input_stack_pages(input_source: &Configuration) -> (LayoutBox, StackSid
 
     for d in HANDLERS.iter().enumerate() {
         if let (idx, Some(_)) = d {
-            let input_editor = build_input_editor(&String::from_utf8(input_source.load_input((idx + 1) as u8)).unwrap());
+            let day = (idx + 1) as u8;
+            let input = input_source.load_input(day).unwrap_or_else(|error| {
+                eprintln!("day {day}: {error}");
+                Vec::new()
+            });
+            let input_editor = build_input_editor(&String::from_utf8_lossy(&input));
             let name = format!("day_{}", idx);
             let label = format!("Day {}", idx + 1);
             stack.add_titled(&input_editor, Some(&name), &label);
@@ -122,20 +128,34 @@ fn input_from_input_sidebar(sidebar: &StackSidebar, day: usize) -> String {
     buffer.text(&mut buffer.start_iter(), &mut buffer.end_iter(), false).to_string()
 }
 
-fn perform_run(text: TextBuffer, grid: Grid, sidebar: StackSidebar, verbose: CheckButton) {
+fn perform_run(text: TextBuffer, grid: Grid, sidebar: StackSidebar, verbose: CheckButton, config: &Configuration) {
     let (sender, receiver) = channel();
     let run_verbose = verbose.is_active();
 
     for day in active_from_day_selector_grid(&grid) {
         let input = input_from_input_sidebar(&sidebar, day);
-        let Day { terse, verbose } = HANDLERS[day].as_ref()
+        let handler = HANDLERS[day].as_ref()
             .expect("Active days are available");
         let mut wrapper = WrapSender(sender.clone(), Vec::new());
+        if input.trim().is_empty() {
+            _ = writeln!(wrapper, "day {}: input is empty", day + 1);
+            _ = wrapper.flush();
+            continue;
+        }
+        let part = config.part;
+        let parameters = config.parameters((day + 1) as u8);
+        let expected = if config.verify {
+            Some(config.load_expected_answers((day + 1) as u8))
+        } else {
+            None
+        };
         run_on_worker(move || {
-            if run_verbose {
-                verbose(input.as_bytes(), &mut wrapper)
-            } else {
-                terse(input.as_bytes(), &mut wrapper)
+            let result = handler.execute((day + 1) as u8, run_verbose, part, &parameters, input.as_bytes(), &mut wrapper);
+
+            if let Some(expected) = expected {
+                let verification = DayVerification::new((day + 1) as u8, expected, result.answers);
+                _ = writeln!(wrapper, "{verification}");
+                _ = wrapper.flush();
             }
         });
     }
@@ -161,7 +181,7 @@ fn install_ui_update_callback(recv: Receiver<String>, text: TextBuffer) {
                       });
 }
 
-fn build_big_run_button(text: TextBuffer, grid: Grid, sidebar: StackSidebar, verbose: CheckButton) -> Button {
+fn build_big_run_button(text: TextBuffer, grid: Grid, sidebar: StackSidebar, verbose: CheckButton, config: Rc<Configuration>) -> Button {
     let button = Button::builder()
         .label("Run selected")
         .build();
@@ -171,8 +191,9 @@ fn build_big_run_button(text: TextBuffer, grid: Grid, sidebar: StackSidebar, ver
         #[weak] grid,
         #[weak] sidebar,
         #[weak] verbose,
+        #[strong] config,
         move |_b| {
-            perform_run(text, grid, sidebar, verbose)
+            perform_run(text, grid, sidebar, verbose, &config)
         }
     ));
 
@@ -186,7 +207,7 @@ fn build_verbose_control(initial: bool) -> CheckButton {
         .build()
 }
 
-fn build_ui(app: &Application, config: &Configuration) {
+fn build_ui(app: &Application, config: &Rc<Configuration>) {
 
     let layout = Grid::builder()
         .column_spacing(4)
@@ -199,7 +220,7 @@ fn build_ui(app: &Application, config: &Configuration) {
     layout.attach(&page_box, 0, 2, 2, 1);
     let (text, widget) = build_output_view();
     layout.attach(&widget, 0, 3, 2, 1);
-    let button = build_big_run_button(text, day_selector_grid, selector, verbose);
+    let button = build_big_run_button(text, day_selector_grid, selector, verbose, config.clone());
     layout.attach(&button, 1, 1, 1, 1);
 
     ApplicationWindow::builder()
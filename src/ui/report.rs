@@ -0,0 +1,248 @@
+use std::fmt::Write;
+use std::time::Duration;
+use crate::day::DayResult;
+use crate::timed::Statistics;
+
+/** Result of a single day, as recorded by a runner */
+pub struct DayRecord<'a> {
+    pub day: u8,
+    pub wall_time: Duration,
+    pub result: &'a DayResult,
+}
+
+/** Timing statistics of a single phase of a day, as measured by the benchmark runner */
+pub struct BenchRecord {
+    pub day: u8,
+    pub phase: &'static str,
+    pub statistics: Statistics,
+}
+
+const CSV_HEADER: &str = "day,part1,part2,error,wall_time_ns,parse_ns,part1_ns,part2_ns,combined_ns,unconsumed_bytes";
+
+const BENCH_CSV_HEADER: &str = "day,phase,samples,min_ns,median_ns,mean_ns,p95_ns,stddev_ns";
+
+fn json_string(value: Option<&str>) -> String {
+    let Some(value) = value else {
+        return "null".to_string();
+    };
+
+    let mut escaped = String::with_capacity(value.len() + 2);
+    escaped.push('"');
+    for char in value.chars() {
+        match char {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if c.is_control() => _ = write!(escaped, "\\u{:04x}", c as u32),
+            c => escaped.push(c),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
+
+fn json_nanos(value: Option<Duration>) -> String {
+    value.map_or("null".to_string(), |duration| duration.as_nanos().to_string())
+}
+
+fn csv_field(value: Option<&str>) -> String {
+    let value = value.unwrap_or_default();
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn csv_nanos(value: Option<Duration>) -> String {
+    value.map_or(String::new(), |duration| duration.as_nanos().to_string())
+}
+
+/** JSON lines - a single object per day, durations given in nanoseconds */
+pub fn render_json(records: &[DayRecord]) -> String {
+    let mut output = String::new();
+    for DayRecord { day, wall_time, result } in records {
+        let timings = &result.timings;
+        _ = writeln!(
+            output,
+            "{{\"day\":{day},\"part1\":{},\"part2\":{},\"error\":{},\"wall_time_ns\":{},\"parse_ns\":{},\"part1_ns\":{},\"part2_ns\":{},\"combined_ns\":{},\"unconsumed_bytes\":{}}}",
+            json_string(result.answers.part1.as_deref()),
+            json_string(result.answers.part2.as_deref()),
+            json_string(result.error.as_deref()),
+            wall_time.as_nanos(),
+            json_nanos(timings.parse),
+            json_nanos(timings.part1),
+            json_nanos(timings.part2),
+            json_nanos(timings.combined),
+            result.unconsumed_bytes
+        );
+    }
+
+    output
+}
+
+/** CSV with a header line, durations given in nanoseconds */
+pub fn render_csv(records: &[DayRecord]) -> String {
+    let mut output = String::new();
+    _ = writeln!(output, "{CSV_HEADER}");
+    for DayRecord { day, wall_time, result } in records {
+        let timings = &result.timings;
+        _ = writeln!(
+            output,
+            "{day},{},{},{},{},{},{},{},{},{}",
+            csv_field(result.answers.part1.as_deref()),
+            csv_field(result.answers.part2.as_deref()),
+            csv_field(result.error.as_deref()),
+            wall_time.as_nanos(),
+            csv_nanos(timings.parse),
+            csv_nanos(timings.part1),
+            csv_nanos(timings.part2),
+            csv_nanos(timings.combined),
+            result.unconsumed_bytes
+        );
+    }
+
+    output
+}
+
+pub fn render_bench_table(records: &[BenchRecord]) -> String {
+    let mut output = String::new();
+    _ = writeln!(
+        output,
+        "{:>3}  {:<8}  {:>7}  {:>12}  {:>12}  {:>12}  {:>12}  {:>12}",
+        "Day", "Phase", "Samples", "Min", "Median", "Mean", "P95", "StdDev"
+    );
+    for BenchRecord { day, phase, statistics } in records {
+        let Statistics { samples, min, median, mean, p95, stddev } = statistics;
+        _ = writeln!(
+            output,
+            "{day:>3}  {phase:<8}  {samples:>7}  {:>12}  {:>12}  {:>12}  {:>12}  {:>12}",
+            format!("{min:?}"),
+            format!("{median:?}"),
+            format!("{mean:?}"),
+            format!("{p95:?}"),
+            format!("{stddev:?}")
+        );
+    }
+
+    output
+}
+
+/** JSON lines - a single object per day and phase, durations given in nanoseconds */
+pub fn render_bench_json(records: &[BenchRecord]) -> String {
+    let mut output = String::new();
+    for BenchRecord { day, phase, statistics } in records {
+        let Statistics { samples, min, median, mean, p95, stddev } = statistics;
+        _ = writeln!(
+            output,
+            "{{\"day\":{day},\"phase\":\"{phase}\",\"samples\":{samples},\"min_ns\":{},\"median_ns\":{},\"mean_ns\":{},\"p95_ns\":{},\"stddev_ns\":{}}}",
+            min.as_nanos(),
+            median.as_nanos(),
+            mean.as_nanos(),
+            p95.as_nanos(),
+            stddev.as_nanos()
+        );
+    }
+
+    output
+}
+
+pub fn render_bench_csv(records: &[BenchRecord]) -> String {
+    let mut output = String::new();
+    _ = writeln!(output, "{BENCH_CSV_HEADER}");
+    for BenchRecord { day, phase, statistics } in records {
+        let Statistics { samples, min, median, mean, p95, stddev } = statistics;
+        _ = writeln!(
+            output,
+            "{day},{phase},{samples},{},{},{},{},{}",
+            min.as_nanos(),
+            median.as_nanos(),
+            mean.as_nanos(),
+            p95.as_nanos(),
+            stddev.as_nanos()
+        );
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+    use crate::day::{Answers, DayResult, Timings};
+    use crate::ui::report::{csv_field, json_string, render_csv, render_json, DayRecord};
+
+    #[test]
+    fn json_escapes_quotes_and_backslashes() {
+        assert_eq!(r#""say \"hi\"""#, json_string(Some(r#"say "hi""#)));
+        assert_eq!(r#""C:\\inputs\\01""#, json_string(Some(r"C:\inputs\01")));
+        assert_eq!("null", json_string(None));
+    }
+
+    #[test]
+    fn json_escapes_control_characters() {
+        assert_eq!(r#""a\nb\r\tc""#, json_string(Some("a\nb\r\tc")));
+        assert_eq!(r#""bell\u0007 nul\u0000""#, json_string(Some("bell\u{7} nul\0")));
+        assert_eq!("\"ünïcödé\"", json_string(Some("ünïcödé")));
+    }
+
+    #[test]
+    fn csv_quotes_only_when_needed() {
+        assert_eq!("\"4,6,3\"", csv_field(Some("4,6,3")));
+        assert_eq!("\"say \"\"hi\"\"\"", csv_field(Some("say \"hi\"")));
+        assert_eq!("\"two\nlines\"", csv_field(Some("two\nlines")));
+        assert_eq!("\"crlf\r\n\"", csv_field(Some("crlf\r\n")));
+        assert_eq!("plain", csv_field(Some("plain")));
+        assert_eq!("", csv_field(None));
+    }
+
+    fn failed() -> DayResult {
+        DayResult {
+            error: Some("panicked: \"bad\" input\nat line 3".to_string()),
+            ..DayResult::default()
+        }
+    }
+
+    fn half_solved() -> DayResult {
+        DayResult {
+            answers: Answers { part1: Some("co,de,ka,ta".to_string()), part2: None },
+            timings: Timings { parse: Some(Duration::from_nanos(10)), part1: Some(Duration::from_nanos(20)), ..Timings::default() },
+            ..DayResult::default()
+        }
+    }
+
+    #[test]
+    fn json_of_error_and_unsolved_rows() {
+        let failed = failed();
+        let half_solved = half_solved();
+        let records = [
+            DayRecord { day: 3, wall_time: Duration::from_nanos(5), result: &failed },
+            DayRecord { day: 23, wall_time: Duration::from_nanos(40), result: &half_solved },
+        ];
+
+        assert_eq!(
+            concat!(
+                r#"{"day":3,"part1":null,"part2":null,"error":"panicked: \"bad\" input\nat line 3","wall_time_ns":5,"parse_ns":null,"part1_ns":null,"part2_ns":null,"combined_ns":null,"unconsumed_bytes":0}"#, "\n",
+                r#"{"day":23,"part1":"co,de,ka,ta","part2":null,"error":null,"wall_time_ns":40,"parse_ns":10,"part1_ns":20,"part2_ns":null,"combined_ns":null,"unconsumed_bytes":0}"#, "\n",
+            ),
+            render_json(&records)
+        );
+    }
+
+    #[test]
+    fn csv_of_error_and_unsolved_rows() {
+        let failed = failed();
+        let half_solved = half_solved();
+        let records = [
+            DayRecord { day: 3, wall_time: Duration::from_nanos(5), result: &failed },
+            DayRecord { day: 23, wall_time: Duration::from_nanos(40), result: &half_solved },
+        ];
+
+        let csv = render_csv(&records);
+        let mut lines = csv.split_inclusive('\n');
+        assert_eq!(Some("day,part1,part2,error,wall_time_ns,parse_ns,part1_ns,part2_ns,combined_ns,unconsumed_bytes\n"), lines.next());
+        assert_eq!("3,,,\"panicked: \"\"bad\"\" input\nat line 3\",5,,,,,0\n23,\"co,de,ka,ta\",,,40,10,20,,,0\n", lines.collect::<String>());
+    }
+}
@@ -0,0 +1,173 @@
+use std::fmt::{Display, Formatter};
+use crate::day::Answers;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Verdict {
+    PASS,
+    FAIL,
+    UNKNOWN,
+}
+
+impl Verdict {
+    fn judge(expected: Option<&str>, actual: Option<&str>) -> Self {
+        match (expected, actual) {
+            (None, _) => Verdict::UNKNOWN,
+            (Some(expected), Some(actual)) if expected == actual => Verdict::PASS,
+            _ => Verdict::FAIL,
+        }
+    }
+}
+
+impl Display for Verdict {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!("{self:?}"))
+    }
+}
+
+/**
+Expected answers file format: the answer to part 1 on the first line, the answer to part 2 on the
+second. Blank or missing lines leave the respective part unknown.
+*/
+pub fn parse_expected_answers(contents: &str) -> Answers {
+    let mut lines = contents.lines().map(|line| {
+        let line = line.trim();
+        if line.is_empty() {
+            None
+        } else {
+            Some(line.to_string())
+        }
+    });
+
+    Answers {
+        part1: lines.next().flatten(),
+        part2: lines.next().flatten(),
+    }
+}
+
+pub struct DayVerification {
+    day: u8,
+    expected: Answers,
+    actual: Answers,
+}
+
+impl DayVerification {
+    pub fn new(day: u8, expected: Answers, actual: Answers) -> Self {
+        Self { day, expected, actual }
+    }
+
+    pub fn verdicts(&self) -> [Verdict; 2] {
+        [
+            Verdict::judge(self.expected.part1.as_deref(), self.actual.part1.as_deref()),
+            Verdict::judge(self.expected.part2.as_deref(), self.actual.part2.as_deref()),
+        ]
+    }
+
+    pub fn failed(&self) -> bool {
+        self.verdicts().contains(&Verdict::FAIL)
+    }
+}
+
+impl Display for DayVerification {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let expected = [&self.expected.part1, &self.expected.part2];
+        let actual = [&self.actual.part1, &self.actual.part2];
+
+        f.write_fmt(format_args!("Day {:02}:", self.day))?;
+        for (part, verdict) in self.verdicts().into_iter().enumerate() {
+            f.write_fmt(format_args!(" part {} {verdict}", part + 1))?;
+            if verdict == Verdict::FAIL {
+                f.write_fmt(format_args!(
+                    " (expected {}, got {})",
+                    expected[part].as_deref().unwrap_or("-"),
+                    actual[part].as_deref().unwrap_or("nothing")
+                ))?;
+            }
+            if part == 0 {
+                f.write_str(",")?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::day::{Answers, Day, DayResult, Part};
+    use crate::parameters::Parameters;
+    use crate::verify::{parse_expected_answers, DayVerification, Verdict};
+
+    fn answers(part1: Option<&str>, part2: Option<&str>) -> Answers {
+        Answers { part1: part1.map(str::to_string), part2: part2.map(str::to_string) }
+    }
+
+    fn verdicts(expected: Answers, actual: Answers) -> [Verdict; 2] {
+        DayVerification::new(1, expected, actual).verdicts()
+    }
+
+    #[test]
+    fn answers_on_one_or_two_lines() {
+        assert_eq!(answers(Some("11"), None), parse_expected_answers("11\n"));
+        assert_eq!(answers(Some("11"), Some("31")), parse_expected_answers("11\n31\n"));
+        assert_eq!(answers(Some("11"), Some("31")), parse_expected_answers("11\n31"));
+        assert_eq!(answers(None, Some("31")), parse_expected_answers("\n31\n"));
+    }
+
+    #[test]
+    fn answers_ignore_trailing_whitespace_and_crlf() {
+        assert_eq!(answers(Some("11"), Some("co,de,ka,ta")), parse_expected_answers("11  \r\nco,de,ka,ta\t\r\n\r\n"));
+    }
+
+    #[test]
+    fn empty_answers_file() {
+        assert_eq!(answers(None, None), parse_expected_answers(""));
+        assert_eq!(answers(None, None), parse_expected_answers("\n  \n"));
+    }
+
+    #[test]
+    fn pass_fail_and_unknown() {
+        assert_eq!(
+            [Verdict::PASS, Verdict::FAIL],
+            verdicts(answers(Some("11"), Some("31")), answers(Some("11"), Some("30")))
+        );
+        assert_eq!(
+            [Verdict::UNKNOWN, Verdict::UNKNOWN],
+            verdicts(answers(None, None), answers(Some("11"), None))
+        );
+        assert_eq!(
+            [Verdict::PASS, Verdict::FAIL],
+            verdicts(answers(Some("11"), Some("31")), answers(Some("11"), None))
+        );
+    }
+
+    #[test]
+    fn failure_is_described() {
+        let verification = DayVerification::new(7, answers(Some("11"), Some("31")), answers(Some("11"), None));
+
+        assert!(verification.failed());
+        assert_eq!("Day 07: part 1 PASS, part 2 FAIL (expected 31, got nothing)", verification.to_string());
+    }
+
+    fn panicking(_: &[u8], _: Part, _: &mut Vec<u8>) -> DayResult {
+        panic!("boom")
+    }
+
+    fn erroring(_: &[u8], _: Part, _: &mut Vec<u8>) -> DayResult {
+        DayResult { error: Some("Parsing failed".to_string()), ..DayResult::default() }
+    }
+
+    #[test]
+    fn failed_days_fail_known_answers() {
+        for handler in [panicking, erroring] {
+            let day = Day { terse: handler, verbose: handler };
+            let result = day.execute(1, false, Part::Both, &Parameters::default(), b"", &mut Vec::new());
+            assert!(result.error.is_some());
+
+            let known = DayVerification::new(1, answers(Some("11"), Some("31")), result.answers.clone());
+            assert_eq!([Verdict::FAIL, Verdict::FAIL], known.verdicts());
+            assert!(known.failed());
+            let unknown = DayVerification::new(1, answers(None, None), result.answers);
+            assert!(!unknown.failed());
+        }
+    }
+}
@@ -1,3 +1,4 @@
+use std::panic::{catch_unwind, resume_unwind, AssertUnwindSafe};
 use std::sync::mpsc::{channel, sync_channel};
 use lazy_static::lazy_static;
 use threadpool::ThreadPool;
@@ -38,15 +39,20 @@ where
     for f in tasks.into_iter(){
         let send = send.clone();
         run_on_worker(move || {
-            let result = f();
-            send.send(result).unwrap();
+            // a panicking task is forwarded to the caller, rather than silently
+            // dropping its result along with the sender
+            let result = catch_unwind(AssertUnwindSafe(f));
+            _ = send.send(result);
         });
     }
     drop(send);
 
     let mut tagged_answers = Vec::new();
     while let Ok(tpl) = recv.recv() {
-        tagged_answers.push(tpl)
+        match tpl {
+            Ok(tpl) => tagged_answers.push(tpl),
+            Err(panic) => resume_unwind(panic),
+        }
     }
     tagged_answers
 }
@@ -112,6 +118,10 @@ mod test {
         fn critical(&mut self, _fmt: Arguments<'_>) {}
 
         fn result(&mut self, _fmt: Arguments<'_>) {}
+
+        fn shows_info(&self) -> bool {
+            false
+        }
     }
 
     #[test]
@@ -150,4 +160,18 @@ mod test {
         let result = parallelize(tasks);
         assert_eq!(Vec::from_iter(0..50usize), result)
     }
+
+    #[test]
+    #[should_panic(expected = "task failed")]
+    fn propagates_panic_in_parallelize() {
+        let tasks = (0usize..10).map(|n| {
+            move || {
+                if n == 5 {
+                    panic!("task failed")
+                }
+                n
+            }
+        }).collect::<Vec<_>>();
+        parallelize(tasks);
+    }
 }
\ No newline at end of file